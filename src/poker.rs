@@ -1,6 +1,41 @@
 // Card Related Structures
 
-#[derive(Debug, PartialEq, Clone)]
+use core::convert::TryFrom;
+use rand::seq::SliceRandom;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::ToOwned, boxed::Box, format, string::String, vec, vec::Vec};
+
+pub mod display;
+pub mod error;
+pub mod hand_eval;
+pub mod notation;
+pub mod simulation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use self::display::{configure_display, display_config, DisplayConfig};
+pub use self::error::{
+    InvalidRank, InvalidSuit, ParseCardError, ParseDeckError, ParseError, ParseRankError,
+    ParseSuitError, PokerError, ValidationError,
+};
+pub use self::hand_eval::{
+    best_five_from_seven, evaluate_hand, generate_all_5card_hands, group_by_rank, hand_score,
+    is_flush, is_royal_flush, is_straight, HandValues, Pair, ThreeOfAKind,
+};
+pub use self::notation::{
+    parse_hand_notation, parse_pokerstars_hand_history, CompletedHand, Notation, SeatedPlayer,
+};
+
+pub use self::simulation::{
+    blocker_effect, combo_count, position_adjusted_range, preflop_hand_strength, run_out,
+    simulate_hand, Range, SimulationResult,
+};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Diamond,
     Heart,
@@ -8,21 +43,66 @@ pub enum Suit {
     Spade,
 }
 
-impl std::fmt::Display for Suit {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            Suit::Heart => write!(f, "♥"),
-            Suit::Diamond => write!(f, "♦"),
-            Suit::Spade => write!(f, "♠"),
-            Suit::Club => write!(f, "♣"),
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Diamond, Suit::Heart, Suit::Club, Suit::Spade];
+
+    // The lowercase letter used in short card names such as "Ah"
+    fn letter(self) -> char {
+        match self {
+            Suit::Diamond => 'd',
+            Suit::Heart => 'h',
+            Suit::Club => 'c',
+            Suit::Spade => 's',
+        }
+    }
+
+    fn icon(self) -> char {
+        match self {
+            Suit::Diamond => '♦',
+            Suit::Heart => '♥',
+            Suit::Club => '♣',
+            Suit::Spade => '♠',
         }
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+impl core::str::FromStr for Suit {
+    type Err = ParseSuitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Suit::try_from(c).map_err(|_| ParseSuitError(s.to_owned()));
+        }
+
+        match s.to_lowercase().as_str() {
+            "diamond" | "diamonds" => Ok(Suit::Diamond),
+            "heart" | "hearts" => Ok(Suit::Heart),
+            "club" | "clubs" => Ok(Suit::Club),
+            "spade" | "spades" => Ok(Suit::Spade),
+            _ => Err(ParseSuitError(s.to_owned())),
+        }
+    }
+}
+
+impl core::convert::TryFrom<char> for Suit {
+    type Error = InvalidSuit;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'd' | 'D' => Ok(Suit::Diamond),
+            'h' | 'H' => Ok(Suit::Heart),
+            'c' | 'C' => Ok(Suit::Club),
+            's' | 'S' => Ok(Suit::Spade),
+            _ => Err(InvalidSuit(c)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rank {
     Ace,
-    One,
     Two,
     Three,
     Four,
@@ -37,279 +117,4165 @@ pub enum Rank {
     King,
 }
 
-impl std::fmt::Display for Rank {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "")
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
+    // Aces play high by default, see HoleCards::is_connected for the wheel
+    pub fn numeric_value(&self) -> u8 {
+        match *self {
+            Rank::Two => 2,
+            Rank::Three => 3,
+            Rank::Four => 4,
+            Rank::Five => 5,
+            Rank::Six => 6,
+            Rank::Seven => 7,
+            Rank::Eight => 8,
+            Rank::Nine => 9,
+            Rank::Ten => 10,
+            Rank::Jack => 11,
+            Rank::Queen => 12,
+            Rank::King => 13,
+            Rank::Ace => 14,
+        }
     }
-}
 
-#[derive(Debug, PartialEq, Clone)]
-pub struct Card(Suit, Rank);
+    // How far apart two ranks are with aces high, so J-9 is 2 and A-2 is 12
+    pub fn gap_to(&self, other: &Rank) -> u8 {
+        self.numeric_value().abs_diff(other.numeric_value())
+    }
 
-impl std::fmt::Display for Card {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "")
+    // The same with aces low, so A-2 is 1 and A-K is 12
+    pub fn gap_to_ace_low(&self, other: &Rank) -> u8 {
+        let low = |rank: &Rank| match rank {
+            Rank::Ace => 1,
+            rank => rank.numeric_value(),
+        };
+        low(self).abs_diff(low(other))
     }
 }
 
-pub type Deck = Vec<Card>;
+// The inverse of Rank::numeric_value, so aces are 14
+impl core::convert::TryFrom<u8> for Rank {
+    type Error = InvalidRank;
 
-// Game Related Structures
-
-#[derive(Debug, PartialEq, Clone)]
-pub struct Player {
-    name: String,
-    chips: usize,
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Rank::ALL
+            .iter()
+            .find(|rank| rank.numeric_value() == value)
+            .cloned()
+            .ok_or(InvalidRank(value))
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub enum PlayerMove {
-    Fold,
-    Check,
-    Bet(usize),
-    Raise(usize),
+impl core::str::FromStr for Rank {
+    type Err = ParseRankError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "a" | "ace" => Ok(Rank::Ace),
+            "2" | "two" => Ok(Rank::Two),
+            "3" | "three" => Ok(Rank::Three),
+            "4" | "four" => Ok(Rank::Four),
+            "5" | "five" => Ok(Rank::Five),
+            "6" | "six" => Ok(Rank::Six),
+            "7" | "seven" => Ok(Rank::Seven),
+            "8" | "eight" => Ok(Rank::Eight),
+            "9" | "nine" => Ok(Rank::Nine),
+            "t" | "10" | "ten" => Ok(Rank::Ten),
+            "j" | "jack" => Ok(Rank::Jack),
+            "q" | "queen" => Ok(Rank::Queen),
+            "k" | "king" => Ok(Rank::King),
+            _ => Err(ParseRankError(s.to_owned())),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub struct HoleCards(Card, Card);
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Card(Suit, Rank);
 
-#[derive(Debug, PartialEq, Clone)]
-pub enum PlayerState {
-    WaitingToBeDealt,
-    Dealt(HoleCards),
-    Active(HoleCards),
-    Folded,
-}
+impl Card {
+    pub fn suit(&self) -> Suit {
+        self.0
+    }
 
-pub struct Hand {
-    players: Vec<(Player, PlayerState)>,
-    deck: Deck,
-    pot: usize,
-    // community_cards: Vec<Card>,
-}
+    pub fn rank(&self) -> Rank {
+        self.1
+    }
 
-pub fn new_hand(players: Vec<Player>, deck: Deck) -> Hand {
-    Hand {
-        players: vec![],
-        pot: 0,
-        deck,
+    // "Ah", the same form that Card::from_str reads
+    fn short_name(&self) -> String {
+        format!("{}{}", self.1, self.0.letter())
+    }
+
+    pub fn is_suited_with(&self, other: &Card) -> bool {
+        self.0 == other.0
+    }
+
+    // From the Playing Cards block, which has a Knight between the Jack and
+    // Queen that isn't used here
+    pub fn unicode_symbol(&self) -> char {
+        let suit = match self.0 {
+            Suit::Spade => 0x1F0A0,
+            Suit::Heart => 0x1F0B0,
+            Suit::Diamond => 0x1F0C0,
+            Suit::Club => 0x1F0D0,
+        };
+        let rank = match self.1 {
+            Rank::Ace => 1,
+            Rank::Queen => 13,
+            Rank::King => 14,
+            other => u32::from(other.numeric_value()),
+        };
+        core::char::from_u32(suit + rank).expect("playing card codepoint")
     }
 }
 
-pub fn deal(hand: Hand) -> Hand {
-    hand
+impl core::str::FromStr for Card {
+    type Err = ParseCardError;
+
+    // Accepts both compact ("Ah", "Td") and verbose ("Ace of Hearts") notation
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // Lowercasing can change byte lengths, so the separator has to be
+        // found in `s` itself
+        let separator = s
+            .as_bytes()
+            .windows(4)
+            .position(|window| window.eq_ignore_ascii_case(b" of "));
+        let (rank, suit) = match separator {
+            Some(i) => (&s[..i], &s[i + 4..]),
+            None => {
+                let split = s.char_indices().last().map_or(0, |(i, _)| i);
+                s.split_at(split)
+            }
+        };
+
+        let rank = rank.parse().map_err(ParseCardError::InvalidRank)?;
+        let suit = suit.parse().map_err(ParseCardError::InvalidSuit)?;
+        Ok(Card(suit, rank))
+    }
 }
 
-pub fn play(hand: Hand, mv: PlayerMove) -> Hand {
-    hand
+// Renders cards for a particular kind of output
+pub trait CardFormatter {
+    fn format_card(&self, card: &Card) -> String;
+
+    fn format_hand(&self, hand: &[Card]) -> String {
+        hand.iter()
+            .map(|card| self.format_card(card))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }
 
-// Unused but potentially interesting follow-on structures
+// "A♠"
+#[derive(Debug, Default)]
+pub struct UnicodeFormatter;
 
-pub enum PlayerPosition {
-    Button,
-    SmallBlind,
-    BigBlind,
+impl CardFormatter for UnicodeFormatter {
+    fn format_card(&self, card: &Card) -> String {
+        format!("{}{}", card.rank(), card.suit().icon())
+    }
 }
 
-pub enum Deal {
-    Hole,
-    Flop,
-    River,
-    Turn,
+// "[As]"
+#[derive(Debug, Default)]
+pub struct AsciiFormatter;
+
+impl CardFormatter for AsciiFormatter {
+    fn format_card(&self, card: &Card) -> String {
+        format!("[{}]", card.short_name())
+    }
 }
 
-pub enum HandValues {
-    HighCard(Card),
-    Pair((Card, Card)),
-    TwoPairs((Card, Card), (Card, Card)),
-    ThreeOfAKind((Card, Card, Card)),
-    Straight((Card, Card, Card, Card, Card)),
-    Flush((Card, Card, Card, Card, Card)),
-    FullHouse((Card, Card, Card, Card, Card)),
-    FourOfAKind((Card, Card, Card, Card)),
-    StraightFlush((Card, Card, Card, Card, Card)),
+// Hearts and diamonds in red, clubs and spades in black
+#[derive(Debug, Default)]
+pub struct HtmlFormatter;
+
+impl CardFormatter for HtmlFormatter {
+    fn format_card(&self, card: &Card) -> String {
+        let color = match card.suit() {
+            Suit::Heart | Suit::Diamond => "red",
+            Suit::Club | Suit::Spade => "black",
+        };
+        format!(
+            "<span class=\"card\" style=\"color: {}\">{}{}</span>",
+            color,
+            card.rank(),
+            card.suit().icon()
+        )
+    }
 }
 
-// Tests
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deck(Vec<Card>);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl Deck {
+    pub fn new() -> Deck {
+        Deck(vec![])
+    }
 
-    #[test]
-    fn suits_display_as_icons_and_ranks_as_text() {
-        // Hint: The format! macro makes use of the Display Trait.
-        // How can we display different values for different enumerations?
-        assert_eq!("(♥, Ace)", format!("{}", Card(Suit::Heart, Rank::Ace)));
-        assert_eq!("(♦, Ten)", format!("{}", Card(Suit::Diamond, Rank::Ten)));
-        assert_eq!("(♣, Ace)", format!("{}", Card(Suit::Club, Rank::Ace)));
-        assert_eq!("(♠, Jack)", format!("{}", Card(Suit::Spade, Rank::Jack)));
+    pub fn push(&mut self, card: Card) {
+        self.0.push(card)
     }
 
-    #[test]
-    fn new_hand_results_in_all_players_waiting_and_pot_of_zero() {
-        let hand = new_hand(
-            vec![
-                Player {
-                    name: s("Will"),
-                    chips: 10,
-                },
-                Player {
-                    name: s("Jean"),
-                    chips: 2,
-                },
-            ],
-            simple_deck(),
-        );
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
 
-        assert_eq!(hand.pot, 0);
-        assert_eq!(hand.deck, simple_deck());
-        assert_eq!(hand.players.len(), 2);
-        for player in hand.players.iter() {
-            assert_eq!(player.1, PlayerState::WaitingToBeDealt);
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Card> {
+        self.0.iter()
+    }
+
+    // Takes the top card off the deck
+    pub fn deal_card(&mut self) -> Option<Card> {
+        if self.0.is_empty() {
+            None
+        } else {
+            Some(self.0.remove(0))
         }
     }
 
-    #[test]
-    fn deal_provides_cards_from_deck_and_sets_first_player_active() {
-        let will = Player {
-            name: s("Will"),
-            chips: 10,
-        };
-        let hand = new_hand(
-            vec![
-                will.clone(),
-                Player {
-                    name: s("Jean"),
-                    chips: 2,
-                },
-            ],
-            // Note: simple_deck just has some aces
-            simple_deck(),
-        );
+    // Takes the top `n` cards off the deck, or none of them when there
+    // aren't enough
+    pub fn deal_n(&mut self, n: usize) -> Result<Vec<Card>, PokerError> {
+        if n > self.len() {
+            return Err(PokerError::InsufficientCards {
+                need: n,
+                have: self.len(),
+            });
+        }
+        Ok(self.0.drain(..n).collect())
+    }
 
-        let hand = deal(hand);
-        assert_eq!(
-            hand.players.first().unwrap().1,
-            PlayerState::Active(HoleCards(
-                simple_deck()[0].clone(),
-                simple_deck()[1].clone()
-            )),
-        );
-        assert_eq!(
-            hand.players.last().unwrap().1,
-            PlayerState::Dealt(HoleCards(
-                simple_deck()[2].clone(),
-                simple_deck()[3].clone()
-            )),
-        );
+    pub fn bottom(&self) -> Option<&Card> {
+        self.0.last()
     }
 
-    #[test]
-    fn check_leaves_pot_untouched() {
-        let hand = new_hand(
-            vec![
-                Player {
-                    name: s("Will"),
-                    chips: 10,
-                },
-                Player {
-                    name: s("Jean"),
-                    chips: 2,
-                },
-            ],
-            simple_deck(),
-        );
+    // Turns the deck over so the bottom card is on top
+    pub fn reverse(&mut self) {
+        self.0.reverse()
+    }
 
-        let hand = play(hand, PlayerMove::Check);
-        assert_eq!(hand.pot, 0);
+    // The top `n` cards, without taking them off the deck
+    pub fn peek_n(&self, n: usize) -> Option<&[Card]> {
+        self.0.get(..n)
+    }
 
-        // TODO: This should move the active player
-        // assert_eq!(
-        //     hand.players.first().unwrap().1,
-        //     PlayerState::Dealt(HoleCards(
-        //         simple_deck()[0].clone(),
-        //         simple_deck()[1].clone()
-        //     )),
-        // );
-        // assert_eq!(
-        //     hand.players.last().unwrap().1,
-        //     PlayerState::Active(HoleCards(
-        //         simple_deck()[2].clone(),
-        //         simple_deck()[3].clone()
-        //     )),
-        // );
+    // `n` different cards picked at random, leaving the deck as it is. Every
+    // card is returned, in a random order, when the deck holds fewer than `n`.
+    pub fn sample_without_replacement(&self, n: usize, rng: &mut impl rand::Rng) -> Vec<Card> {
+        self.0.choose_multiple(rng, n).cloned().collect()
     }
 
-    #[test]
-    fn bet_increases_the_pot() {
-        let hand = new_hand(
-            vec![
-                Player {
-                    name: s("Will"),
-                    chips: 10,
-                },
-                Player {
-                    name: s("Jean"),
-                    chips: 2, // <--- loser
-                },
-            ],
-            simple_deck(),
-        );
+    // The first `pos` cards from the top, and the rest
+    pub fn split_at(mut self, pos: usize) -> Result<(Deck, Deck), PokerError> {
+        if pos > self.len() {
+            return Err(PokerError::InsufficientCards {
+                need: pos,
+                have: self.len(),
+            });
+        }
+        let rest = self.0.split_off(pos);
+        Ok((self, Deck(rest)))
+    }
 
-        let hand = play(hand, PlayerMove::Bet(3));
-        assert_eq!(hand.pot, 3);
+    // `other` goes underneath this deck
+    pub fn merge(mut self, other: Deck) -> Deck {
+        self.0.extend(other.0);
+        self
+    }
 
-        // TODO: This should move the active player and decrease from active players chips
+    // A faro shuffle: cards go alternately from the top of this deck and
+    // `other`, starting with this one. Whatever is left of the longer deck
+    // goes on the bottom.
+    pub fn interleave(self, other: Deck) -> Deck {
+        let mut cards = Vec::with_capacity(self.len() + other.len());
+        let mut ours = self.0.into_iter();
+        let mut theirs = other.0.into_iter();
+        loop {
+            match (ours.next(), theirs.next()) {
+                (None, None) => return Deck(cards),
+                (our, their) => cards.extend(our.into_iter().chain(their)),
+            }
+        }
     }
 
-    #[test]
-    fn raise_increases_the_pot() {
-        let hand = new_hand(
-            vec![
-                Player {
-                    name: s("Will"),
-                    chips: 10,
-                },
-                Player {
-                    name: s("Jean"),
-                    chips: 2, // <--- loser
-                },
-            ],
-            simple_deck(),
-        );
+    // Cuts the deck exactly in half and interleaves the halves, top half
+    // first. Eight of these put a 52 card deck back in its original order.
+    pub fn perfect_riffle_shuffle(&mut self) {
+        let half = self.len() / 2;
+        let mut bottom = core::mem::take(&mut self.0);
+        let top = bottom.drain(..half).collect::<Deck>();
+        *self = top.interleave(Deck(bottom));
+    }
 
-        let hand = play(hand, PlayerMove::Raise(3));
-        assert_eq!(hand.pot, 3);
+    // A riffle as a person does it: the cut only lands near the middle and
+    // cards drop from each half in clumps. Each card is cut into the top half
+    // on a coin flip, then drops from a half with a chance in proportion to
+    // how many cards that half has left.
+    pub fn imperfect_riffle_shuffle(&mut self, rng: &mut impl rand::Rng) {
+        let cut = (0..self.len()).filter(|_| rng.gen_bool(0.5)).count();
+        let mut bottom = core::mem::take(&mut self.0);
+        let mut top = bottom.drain(..cut).collect::<Vec<_>>().into_iter();
+        let mut bottom = bottom.into_iter();
+        let (mut top_left, mut bottom_left) = (top.len(), bottom.len());
+        while top_left + bottom_left > 0 {
+            let card = if rng.gen_range(0..top_left + bottom_left) < top_left {
+                top_left -= 1;
+                top.next()
+            } else {
+                bottom_left -= 1;
+                bottom.next()
+            };
+            self.0.extend(card);
+        }
+    }
 
-        // TODO: This should move the active player and decrease from active players chips
+    pub fn count_rank(&self, rank: Rank) -> usize {
+        self.iter().filter(|card| card.rank() == rank).count()
     }
 
-    #[test]
-    fn fold_sets_the_player_state_to_folded() {
-        let hand = new_hand(
-            vec![
-                Player {
-                    name: s("Will"),
-                    chips: 10,
-                },
-                Player {
-                    name: s("Jean"),
-                    chips: 2, // <--- loser
-                },
-            ],
-            simple_deck(),
-        );
+    pub fn count_suit(&self, suit: Suit) -> usize {
+        self.iter().filter(|card| card.suit() == suit).count()
+    }
 
-        let hand = play(hand, PlayerMove::Fold);
-        assert_eq!(hand.players.first().unwrap().1, PlayerState::Folded)
+    // A multi-deck shoe made of `num_decks` unshuffled full decks
+    pub fn shoe(num_decks: usize) -> Deck {
+        (0..num_decks).fold(Deck::new(), |shoe, _| shoe.merge(full_deck()))
+    }
+}
 
-        // TODO: This should move the active player
+impl core::ops::Index<usize> for Deck {
+    type Output = Card;
+
+    fn index(&self, index: usize) -> &Card {
+        &self.0[index]
     }
+}
 
-    fn s(s: &str) -> String {
-        s.to_owned()
+impl IntoIterator for Deck {
+    type Item = Card;
+    type IntoIter = alloc::vec::IntoIter<Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Deck {
+    type Item = &'a Card;
+    type IntoIter = core::slice::Iter<'a, Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl core::iter::FromIterator<Card> for Deck {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        Deck(iter.into_iter().collect())
+    }
+}
+
+// New cards go on the bottom
+impl Extend<Card> for Deck {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl Extend<Deck> for Deck {
+    fn extend<I: IntoIterator<Item = Deck>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().flatten())
+    }
+}
+
+impl core::str::FromStr for Deck {
+    type Err = ParseDeckError;
+
+    // Parses space separated cards, e.g. "Ah Kd Qc Js Th", top of the deck first
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = s
+            .split_whitespace()
+            .map(|token| token.parse().map_err(ParseDeckError::InvalidCard))
+            .collect::<Result<Vec<Card>, _>>()?;
+        assert_no_duplicates(&cards).map_err(ParseDeckError::DuplicateCard)?;
+        Ok(Deck(cards))
+    }
+}
+
+// Returns the first card that appears more than once
+fn assert_no_duplicates(cards: &[Card]) -> Result<(), Card> {
+    for (i, card) in cards.iter().enumerate() {
+        if cards[i + 1..].contains(card) {
+            return Err(card.clone());
+        }
+    }
+    Ok(())
+}
+
+pub fn full_deck() -> Deck {
+    Suit::ALL
+        .iter()
+        .flat_map(|suit| Rank::ALL.iter().map(move |rank| Card(*suit, *rank)))
+        .collect()
+}
+
+// Every card as a number from 0 to 51 for lookup tables and bitmasks. Cards
+// are ordered by rank, twos first, then by suit, so the four twos are 0-3.
+pub fn card_to_index(card: &Card) -> u8 {
+    let suit = Suit::ALL
+        .iter()
+        .position(|suit| *suit == card.suit())
+        .expect("every suit is in Suit::ALL") as u8;
+    (card.rank().numeric_value() - 2) * 4 + suit
+}
+
+// The inverse of card_to_index, which panics for anything past 51
+pub fn index_to_card(i: u8) -> Card {
+    assert!(i < 52, "card index {} is out of range", i);
+    let rank = Rank::try_from(i / 4 + 2).expect("every rank has a numeric value from 2 to 14");
+    Card(Suit::ALL[usize::from(i % 4)], rank)
+}
+
+// Shuffling
+
+// Lets callers decide where the randomness comes from, since not every
+// environment has a thread_rng
+pub trait Shuffler {
+    fn shuffle(&self, deck: &mut Deck);
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct ThreadRngShuffler;
+
+#[cfg(feature = "std")]
+impl Shuffler for ThreadRngShuffler {
+    fn shuffle(&self, deck: &mut Deck) {
+        deck.0.shuffle(&mut rand::thread_rng());
+    }
+}
+
+// The same seed always shuffles a deck into the same order
+#[derive(Debug)]
+pub struct SeededShuffler {
+    pub seed: u64,
+}
+
+impl Shuffler for SeededShuffler {
+    fn shuffle(&self, deck: &mut Deck) {
+        use rand::SeedableRng;
+        deck.0
+            .shuffle(&mut rand::rngs::StdRng::seed_from_u64(self.seed));
+    }
+}
+
+// Leaves the deck exactly as it was, which is handy for tests
+#[derive(Debug, Default)]
+pub struct NoopShuffler;
+
+impl Shuffler for NoopShuffler {
+    fn shuffle(&self, _deck: &mut Deck) {}
+}
+
+// Game Related Structures
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Player {
+    name: String,
+    chips: usize,
+    // Extra thinking time for online play
+    #[cfg_attr(feature = "serde", serde(default))]
+    time_bank_seconds: u64,
+}
+
+pub const MAX_TIME_BANK_SECONDS: u64 = 120;
+
+impl Player {
+    pub fn new(name: impl Into<String>, chips: usize) -> Player {
+        Player {
+            name: name.into(),
+            chips,
+            time_bank_seconds: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn chips(&self) -> usize {
+        self.chips
+    }
+
+    pub fn time_bank_seconds(&self) -> u64 {
+        self.time_bank_seconds
+    }
+
+    pub fn is_eligible_to_play(&self) -> bool {
+        self.chips > 0
+    }
+
+    pub fn add_chips(&mut self, amount: usize) {
+        self.chips += amount;
+    }
+
+    pub fn remove_chips(&mut self, amount: usize) -> Result<(), PokerError> {
+        if amount > self.chips {
+            return Err(PokerError::InsufficientChips {
+                have: self.chips,
+                need: amount,
+            });
+        }
+        self.chips -= amount;
+        Ok(())
+    }
+}
+
+// Bigger stacks come first so that sorting gives a leaderboard. Players with
+// the same chips are ordered by name, then time bank, to stay consistent
+// with equality.
+impl PartialOrd for Player {
+    fn partial_cmp(&self, other: &Player) -> Option<core::cmp::Ordering> {
+        Some(
+            other
+                .chips
+                .cmp(&self.chips)
+                .then_with(|| self.name.cmp(&other.name))
+                .then_with(|| self.time_bank_seconds.cmp(&other.time_bank_seconds)),
+        )
+    }
+}
+
+pub trait SortByChips {
+    fn sort_by_chips(&mut self);
+}
+
+impl SortByChips for Vec<Player> {
+    fn sort_by_chips(&mut self) {
+        self.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+    }
+}
+
+impl core::ops::Add<usize> for Player {
+    type Output = Player;
+
+    fn add(mut self, chips: usize) -> Player {
+        self.add_chips(chips);
+        self
+    }
+}
+
+// Panics if the player doesn't have `chips` to take away
+impl core::ops::Sub<usize> for Player {
+    type Output = Player;
+
+    fn sub(mut self, chips: usize) -> Player {
+        self.remove_chips(chips)
+            .expect("can't take away more chips than the player has");
+        self
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlayerMove {
+    Fold,
+    Check,
+    Bet(usize),
+    Call(usize),
+    Raise(usize),
+    // Draw poker: the cards thrown away to be replaced from the deck
+    Discard(Vec<Card>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HoleCards(Card, Card);
+
+pub const MAX_GAP: u8 = 4;
+
+impl HoleCards {
+    pub fn is_suited(&self) -> bool {
+        self.0.is_suited_with(&self.1)
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.0.rank() == self.1.rank()
+    }
+
+    // Ace-King and Ace-Two (the wheel) both count as connected
+    pub fn is_connected(&self) -> bool {
+        self.rank_distance() == 1
+    }
+
+    // Ranks skipped between the two cards, so Q-J is 0 and J-9 is 1. Pairs
+    // are 0 by convention and anything wider than four is just a four gapper.
+    pub fn gap(&self) -> u8 {
+        match self.rank_distance() {
+            0 => 0,
+            distance => core::cmp::min(distance - 1, MAX_GAP),
+        }
+    }
+
+    // Broadway hands beat connectors, which beat gappers. Anything with a gap
+    // of MAX_GAP or more is just suited or offsuit.
+    pub fn hand_category(&self) -> HoleCardCategory {
+        use HoleCardCategory::*;
+        let broadway = |card: &Card| card.rank().numeric_value() >= Rank::Ten.numeric_value();
+        let (suited, offsuit) = if self.is_pair() {
+            return PocketPair;
+        } else if broadway(&self.0) && broadway(&self.1) {
+            (SuitedBroadway, OffSuitBroadway)
+        } else if self.is_connected() {
+            (SuitedConnectors, OffSuitConnectors)
+        } else if self.gap() < MAX_GAP {
+            (SuitedGapper, OffSuitGapper)
+        } else {
+            (Suited, OffSuit)
+        };
+        if self.is_suited() {
+            suited
+        } else {
+            offsuit
+        }
+    }
+
+    fn rank_distance(&self) -> u8 {
+        let (a, b) = (self.0.rank(), self.1.rank());
+        // An ace can also play low, in which case it sits just below the two
+        core::cmp::min(a.gap_to(&b), a.gap_to_ace_low(&b))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum HoleCardCategory {
+    PocketPair,
+    SuitedBroadway,
+    OffSuitBroadway,
+    SuitedConnectors,
+    OffSuitConnectors,
+    SuitedGapper,
+    OffSuitGapper,
+    Suited,
+    OffSuit,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlayerState {
+    WaitingToBeDealt,
+    Dealt(HoleCards),
+    Active(HoleCards),
+    Folded,
+    Mucked,
+    // Still in the hand with their cards face up for everyone to see
+    Tabled(HoleCards),
+    // At the table but not being dealt in
+    SittingOut,
+}
+
+impl PlayerState {
+    pub fn hole_cards(&self) -> Option<&HoleCards> {
+        match self {
+            PlayerState::Dealt(hole) | PlayerState::Active(hole) | PlayerState::Tabled(hole) => {
+                Some(hole)
+            }
+            PlayerState::WaitingToBeDealt
+            | PlayerState::Folded
+            | PlayerState::Mucked
+            | PlayerState::SittingOut => None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hand {
+    players: Vec<(Player, PlayerState)>,
+    deck: Deck,
+    pot: usize,
+    config: GameConfig,
+    community_cards: Vec<Card>,
+    log: GameLog,
+    // What each seat has put in on the current street
+    street_bets: Vec<usize>,
+    // What each seat has put in over the whole hand, antes included
+    chips_committed_this_hand: Vec<usize>,
+    // The seat in the PlayerState::Active state, if any
+    active_player_index: Option<usize>,
+    // Burnt cards and folded hands, out of play without ever being seen
+    discard_pile: Vec<Card>,
+    // What the hand started with, which should never change
+    chips_in_play: usize,
+    cards_in_play: usize,
+}
+
+// Only the active player's hole cards are shown, everyone else's stay hidden
+impl core::fmt::Debug for Hand {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let active = self.active_player_index.map(|seat| {
+            (
+                &self.players[seat].0.name,
+                self.players[seat].1.hole_cards(),
+            )
+        });
+        f.debug_struct("Hand")
+            .field("pot", &self.pot)
+            .field("players", &self.players.len())
+            .field("street", &self.current_street())
+            .field("community_cards", &self.community_cards)
+            .field("active_player", &active)
+            .finish()
+    }
+}
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 10;
+
+impl Hand {
+    // Like new_hand, but refuses a table that can't play: fewer than two
+    // players with chips, more than a hold'em deck can deal to, or a deck
+    // without enough cards for everyone's hole cards and a full board
+    pub fn try_new(players: Vec<Player>, deck: Deck) -> Result<Hand, PokerError> {
+        let hand = new_hand(players, deck);
+        let got = hand.players.len();
+        if got < MIN_PLAYERS {
+            return Err(PokerError::NotEnoughPlayers {
+                min: MIN_PLAYERS,
+                got,
+            });
+        }
+        if got > MAX_PLAYERS {
+            return Err(PokerError::TooManyPlayers {
+                max: MAX_PLAYERS,
+                got,
+            });
+        }
+        let needed = 2 * got + 5;
+        if hand.deck.len() < needed {
+            return Err(PokerError::DeckTooSmall {
+                needed,
+                have: hand.deck.len(),
+            });
+        }
+        Ok(hand)
+    }
+
+    // Deals two cards to each player in turn, skipping anyone sitting out.
+    // The first player dealt in then acts first.
+    pub fn deal(mut self) -> Result<Hand, PokerError> {
+        if self.is_dealt() {
+            return Err(PokerError::AlreadyDealt);
+        }
+        let dealt_in = self
+            .players
+            .iter()
+            .filter(|(_, state)| *state == PlayerState::WaitingToBeDealt)
+            .count();
+        if self.deck.len() < 2 * dealt_in {
+            return Err(PokerError::EmptyDeck);
+        }
+
+        self.log.deck = self.deck.clone();
+        self.log.starting_stacks = self
+            .players
+            .iter()
+            .zip(self.chips_committed_this_hand.iter())
+            .map(|((player, _), committed)| (player.name.clone(), player.chips + committed))
+            .collect();
+        self.active_player_index = None;
+        for (i, (_, state)) in self.players.iter_mut().enumerate() {
+            if *state == PlayerState::SittingOut {
+                continue;
+            }
+            let mut cards = self.deck.deal_n(2)?.into_iter();
+            let hole = match (cards.next(), cards.next()) {
+                (Some(first), Some(second)) => HoleCards(first, second),
+                _ => return Err(PokerError::EmptyDeck),
+            };
+            *state = match self.active_player_index {
+                None => {
+                    self.active_player_index = Some(i);
+                    PlayerState::Active(hole)
+                }
+                Some(_) => PlayerState::Dealt(hole),
+            };
+        }
+
+        Ok(self)
+    }
+
+    // Anything beyond waiting for cards or sitting out means the hand is under way
+    fn is_dealt(&self) -> bool {
+        self.players.iter().any(|(_, state)| {
+            !matches!(
+                state,
+                PlayerState::WaitingToBeDealt | PlayerState::SittingOut
+            )
+        })
+    }
+
+    // Players can only sit out or come back between hands
+    pub fn sit_out(mut self, player_name: &str) -> Result<Hand, PokerError> {
+        let seat = self.seat_of(player_name)?;
+        match self.players[seat].1 {
+            PlayerState::WaitingToBeDealt | PlayerState::SittingOut => {
+                self.players[seat].1 = PlayerState::SittingOut;
+                Ok(self)
+            }
+            _ => Err(PokerError::AlreadyDealt),
+        }
+    }
+
+    pub fn sit_back_in(mut self, player_name: &str) -> Result<Hand, PokerError> {
+        let seat = self.seat_of(player_name)?;
+        match self.players[seat].1 {
+            PlayerState::WaitingToBeDealt | PlayerState::SittingOut => {
+                self.players[seat].1 = PlayerState::WaitingToBeDealt;
+                Ok(self)
+            }
+            _ => Err(PokerError::AlreadyDealt),
+        }
+    }
+
+    pub fn deal_flop(self) -> Result<Hand, PokerError> {
+        self.deal_street(Deal::Flop)
+    }
+
+    pub fn deal_turn(self) -> Result<Hand, PokerError> {
+        self.deal_street(Deal::Turn)
+    }
+
+    pub fn deal_river(self) -> Result<Hand, PokerError> {
+        self.deal_street(Deal::River)
+    }
+
+    // Burns and then deals the community cards for `street`, which has to be
+    // the one after the current street. Betting starts again from nothing
+    // with the first seat still holding cards to act.
+    fn deal_street(mut self, street: Deal) -> Result<Hand, PokerError> {
+        let dealt = self
+            .players
+            .iter()
+            .any(|(_, state)| state.hole_cards().is_some());
+        if !dealt || self.current_street().next() != Some(street) {
+            return Err(PokerError::CannotDeal(street));
+        }
+        let burn = street.burn_count();
+        let needed = burn + street.community_card_count();
+        if self.deck.len() < needed {
+            return Err(PokerError::InsufficientCards {
+                need: needed,
+                have: self.deck.len(),
+            });
+        }
+
+        self.discard_pile.extend(self.deck.deal_n(burn)?);
+        self.community_cards
+            .extend(self.deck.deal_n(street.community_card_count())?);
+        self.street_bets.iter_mut().for_each(|bet| *bet = 0);
+
+        if let Some(active) = self.active_player_index {
+            if let PlayerState::Active(hole) = &self.players[active].1 {
+                self.players[active].1 = PlayerState::Dealt(hole.clone());
+            }
+        }
+        if !self.players.is_empty() {
+            self.advance_action(self.players.len() - 1);
+        }
+        debug_assert_eq!(self.validate(), Ok(()));
+        Ok(self)
+    }
+
+    pub fn play(mut self, mv: PlayerMove) -> Result<Hand, PokerError> {
+        let active = self.active_player_index.ok_or(PokerError::NoActivePlayer)?;
+
+        if let PlayerMove::Discard(cards) = &mv {
+            let name = self.players[active].0.name.clone();
+            for card in cards {
+                let new = self
+                    .deck
+                    .peek_n(1)
+                    .map(|top| top[0].clone())
+                    .ok_or(PokerError::EmptyDeck)?;
+                self = self.replace_card(&name, card.clone(), new)?;
+            }
+        }
+
+        let pot_before = self.pot;
+        let street = self.current_street();
+        let (player, state) = &mut self.players[active];
+        match mv {
+            PlayerMove::Fold => {
+                if let Some(hole) = state.hole_cards() {
+                    self.discard_pile
+                        .extend(vec![hole.0.clone(), hole.1.clone()]);
+                }
+                *state = PlayerState::Folded;
+            }
+            PlayerMove::Check | PlayerMove::Discard(_) => {}
+            PlayerMove::Bet(amount) | PlayerMove::Call(amount) | PlayerMove::Raise(amount) => {
+                player.remove_chips(amount)?;
+                self.pot += amount;
+                self.street_bets[active] += amount;
+                self.chips_committed_this_hand[active] += amount;
+            }
+        }
+
+        self.log.actions.push(ActionRecord {
+            street,
+            player: player.name.clone(),
+            action: mv,
+            pot_before,
+            pot_after: self.pot,
+        });
+        self.advance_action(active);
+        debug_assert_eq!(self.validate(), Ok(()));
+        Ok(self)
+    }
+
+    // Checks the hand hasn't got itself into an impossible state, reporting
+    // every problem found rather than just the first
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+
+        let active = self
+            .players
+            .iter()
+            .filter(|(_, state)| matches!(state, PlayerState::Active(_)))
+            .count();
+        if active > 1 {
+            errors.push(ValidationError::MultipleActivePlayers(active));
+        }
+
+        let chips = self.pot
+            + self
+                .players
+                .iter()
+                .map(|(player, _)| player.chips)
+                .sum::<usize>();
+        if chips != self.chips_in_play {
+            errors.push(ValidationError::ChipsNotConserved {
+                expected: self.chips_in_play,
+                got: chips,
+            });
+        }
+
+        let mut cards: Vec<Card> = self.deck.iter().cloned().collect();
+        cards.extend(self.community_cards.iter().cloned());
+        cards.extend(self.discard_pile.iter().cloned());
+        for hole in self
+            .players
+            .iter()
+            .filter_map(|(_, state)| state.hole_cards())
+        {
+            cards.extend(vec![hole.0.clone(), hole.1.clone()]);
+        }
+        if cards.len() != self.cards_in_play {
+            errors.push(ValidationError::CardsNotConserved {
+                expected: self.cards_in_play,
+                got: cards.len(),
+            });
+        }
+        for (i, card) in cards.iter().enumerate() {
+            if cards[i + 1..].contains(card) && !cards[..i].contains(card) {
+                errors.push(ValidationError::DuplicateCard(card.clone()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // Everything the players have put in so far this hand. There are no side
+    // pots, so until the pot is awarded this is the pot.
+    pub fn total_chips_committed(&self) -> usize {
+        self.chips_committed_this_hand.iter().sum()
+    }
+
+    pub fn active_player_index(&self) -> Option<usize> {
+        self.active_player_index
+    }
+
+    pub fn log(&self) -> &GameLog {
+        &self.log
+    }
+
+    pub fn board(&self) -> &[Card] {
+        &self.community_cards
+    }
+
+    // The community cards in short form, e.g. "Ah Kd Qc"
+    pub fn board_string(&self) -> String {
+        self.community_cards
+            .iter()
+            .map(Card::short_name)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    // Which street is being played, going by the cards on the board
+    pub fn current_street(&self) -> Deal {
+        match self.community_cards.len() {
+            0..=2 => Deal::Hole,
+            3 => Deal::Flop,
+            4 => Deal::Turn,
+            _ => Deal::River,
+        }
+    }
+
+    // Takes up to `amount` from a player without them acting, a player who
+    // can't cover it puts in everything they have
+    fn post_forced_bet(&mut self, index: usize, amount: usize) -> usize {
+        let player = &mut self.players[index].0;
+        let posted = core::cmp::min(amount, player.chips);
+        player
+            .remove_chips(posted)
+            .expect("never posts more than the player has");
+        self.pot += posted;
+        self.chips_committed_this_hand[index] += posted;
+        posted
+    }
+
+    // Unlike antes, blinds count towards what a player has bet on the street
+    fn post_blind(&mut self, index: usize, amount: usize) {
+        self.street_bets[index] += self.post_forced_bet(index, amount);
+    }
+
+    // Moves chips straight from one stack to another, e.g. to pay a bounty.
+    // That has to happen before the deal, since the log has no record of it.
+    pub fn transfer_chips(
+        mut self,
+        from: &str,
+        to: &str,
+        amount: usize,
+    ) -> Result<Hand, PokerError> {
+        if self.is_dealt() {
+            return Err(PokerError::AlreadyDealt);
+        }
+        let from = self.seat_of(from)?;
+        let to = self.seat_of(to)?;
+        self.players[from].0.remove_chips(amount)?;
+        self.players[to].0.add_chips(amount);
+        Ok(self)
+    }
+
+    // Cash game players can top up, but only while they aren't playing a hand
+    pub fn rebuy(mut self, player_name: &str, amount: usize) -> Result<Hand, PokerError> {
+        let seat = self.seat_of(player_name)?;
+        match self.players[seat].1 {
+            PlayerState::WaitingToBeDealt | PlayerState::Folded | PlayerState::SittingOut => {
+                self.players[seat].0.add_chips(amount);
+                self.chips_in_play += amount;
+                Ok(self)
+            }
+            _ => Err(PokerError::RebuyNotAllowed),
+        }
+    }
+
+    // Tops up a player's time bank, which never holds more than
+    // MAX_TIME_BANK_SECONDS
+    pub fn time_bank_extend(mut self, player_name: &str, seconds: u64) -> Result<Hand, PokerError> {
+        let seat = self.seat_of(player_name)?;
+        let player = &mut self.players[seat].0;
+        player.time_bank_seconds =
+            core::cmp::min(player.time_bank_seconds + seconds, MAX_TIME_BANK_SECONDS);
+        Ok(self)
+    }
+
+    pub fn time_bank_use(mut self, player_name: &str, seconds: u64) -> Result<Hand, PokerError> {
+        let seat = self.seat_of(player_name)?;
+        let player = &mut self.players[seat].0;
+        if seconds > player.time_bank_seconds {
+            return Err(PokerError::InsufficientTimeBank {
+                have: player.time_bank_seconds,
+                need: seconds,
+            });
+        }
+        player.time_bank_seconds -= seconds;
+        Ok(self)
+    }
+
+    // A voluntary blind of twice the big blind. The straddler then acts last
+    // before the flop, so the action moves round to the seat after them. Only
+    // one player can straddle, and only before anyone has acted.
+    pub fn straddle(mut self, player_name: &str) -> Result<Hand, PokerError> {
+        let seat = self.seat_of(player_name)?;
+        let acted = self
+            .log
+            .actions
+            .iter()
+            .any(|record| record.street == Deal::Hole);
+        let straddled = self
+            .street_bets
+            .iter()
+            .any(|&bet| bet > self.config.big_blind);
+        if self.current_street() != Deal::Hole
+            || self.players[seat].1.hole_cards().is_none()
+            || acted
+            || straddled
+        {
+            return Err(PokerError::StraddleNotAllowed);
+        }
+
+        let amount = 2 * self.config.big_blind;
+        self.players[seat].0.remove_chips(amount)?;
+        self.pot += amount;
+        self.street_bets[seat] += amount;
+        self.chips_committed_this_hand[seat] += amount;
+
+        if let Some(active) = self.active_player_index {
+            if let PlayerState::Active(hole) = &self.players[active].1 {
+                self.players[active].1 = PlayerState::Dealt(hole.clone());
+            }
+        }
+        self.advance_action(seat);
+        debug_assert_eq!(self.validate(), Ok(()));
+        Ok(self)
+    }
+
+    // Ends the hand as if everyone but `player_name` had folded, so they win
+    // the pot without a showdown and nobody is left to act
+    pub fn fold_all_but(mut self, player_name: &str) -> Result<Hand, PokerError> {
+        let winner = self.seat_of(player_name)?;
+        for (i, (_, state)) in self.players.iter_mut().enumerate() {
+            if i != winner {
+                if let Some(hole) = state.hole_cards() {
+                    self.discard_pile
+                        .extend(vec![hole.0.clone(), hole.1.clone()]);
+                }
+                *state = PlayerState::Folded;
+            } else if let PlayerState::Active(hole) = state {
+                *state = PlayerState::Dealt(hole.clone());
+            }
+        }
+        self.active_player_index = None;
+
+        let pot = core::mem::take(&mut self.pot);
+        self.players[winner].0.add_chips(pot);
+        self.log.winners.push((player_name.to_owned(), pot));
+        Ok(self)
+    }
+
+    // Takes a player away from the table mid hand, say when they disconnect.
+    // Anything they've already bet is forfeited to the pot, and if it was
+    // their turn the action moves on to the next player.
+    pub fn kick_player(mut self, player_name: &str) -> Result<Hand, PokerError> {
+        let seat = self.seat_of(player_name)?;
+        if self.active_player_index == Some(seat) {
+            self.advance_action(seat);
+        }
+
+        let (player, state) = self.players.remove(seat);
+        if let Some(hole) = state.hole_cards() {
+            self.discard_pile
+                .extend(vec![hole.0.clone(), hole.1.clone()]);
+        }
+        self.street_bets.remove(seat);
+        self.chips_committed_this_hand.remove(seat);
+        self.chips_in_play -= player.chips;
+        self.active_player_index = match self.active_player_index {
+            // Nobody else was left to act
+            Some(active) if active == seat => None,
+            Some(active) if active > seat => Some(active - 1),
+            active => active,
+        };
+
+        debug_assert_eq!(self.validate(), Ok(()));
+        Ok(self)
+    }
+
+    // Draw poker: swaps one of a player's cards for a particular card from the
+    // deck, the old card being discarded
+    pub fn replace_card(
+        mut self,
+        player_name: &str,
+        old: Card,
+        new: Card,
+    ) -> Result<Hand, PokerError> {
+        let seat = self.seat_of(player_name)?;
+        let hole = match &mut self.players[seat].1 {
+            PlayerState::Dealt(hole) | PlayerState::Active(hole) => hole,
+            _ => return Err(PokerError::NotInHand(player_name.to_owned())),
+        };
+        let held = if hole.0 == old {
+            &mut hole.0
+        } else if hole.1 == old {
+            &mut hole.1
+        } else {
+            return Err(PokerError::CardNotHeld(old));
+        };
+        let position = self
+            .deck
+            .iter()
+            .position(|card| *card == new)
+            .ok_or_else(|| PokerError::CardNotInDeck(new.clone()))?;
+
+        *held = self.deck.0.remove(position);
+        self.discard_pile.push(old);
+        Ok(self)
+    }
+
+    // Turns a player's cards face up, e.g. once they're all in. A tabled
+    // player has nothing left to decide so the action moves on past them.
+    pub fn force_show_cards(&mut self, player_name: &str) -> Result<(), PokerError> {
+        let seat = self.seat_of(player_name)?;
+        match &self.players[seat].1 {
+            PlayerState::Dealt(hole) => {
+                self.players[seat].1 = PlayerState::Tabled(hole.clone());
+            }
+            PlayerState::Active(hole) => {
+                self.players[seat].1 = PlayerState::Tabled(hole.clone());
+                self.advance_action(seat);
+            }
+            PlayerState::Tabled(_) => {}
+            PlayerState::WaitingToBeDealt
+            | PlayerState::Folded
+            | PlayerState::Mucked
+            | PlayerState::SittingOut => {
+                return Err(PokerError::NoCardsToShow(player_name.to_owned()));
+            }
+        }
+        Ok(())
+    }
+
+    fn seat_of(&self, player_name: &str) -> Result<usize, PokerError> {
+        self.players
+            .iter()
+            .position(|(player, _)| player.name == player_name)
+            .ok_or_else(|| PokerError::PlayerNotFound(player_name.to_owned()))
+    }
+
+    // The EV of the named player calling, see expected_value
+    pub fn expected_value(&self, player: &Player, win_probability: f64) -> f64 {
+        let call = self
+            .players
+            .iter()
+            .position(|(seated, _)| seated.name == player.name)
+            .map_or(0, |seat| self.to_call_for(seat));
+        expected_value(player, call, self.pot, win_probability)
+    }
+
+    // What the active player needs to put in to call, capped at their stack.
+    // This is 0 when they can check.
+    pub fn bet_to_call(&self) -> usize {
+        self.active_player_index
+            .map_or(0, |seat| self.to_call_for(seat))
+    }
+
+    // What the active player's bet would be after a pot-sized raise: a call,
+    // then a raise of the whole pot including that call. With nobody having
+    // bet before this street that's three times the bet plus the pot.
+    pub fn pot_size_raise(&self) -> usize {
+        let current_bet = self.street_bets.iter().max().cloned().unwrap_or_default();
+        let to_call = self
+            .active_player_index
+            .map_or(current_bet, |seat| current_bet - self.street_bets[seat]);
+        current_bet + self.pot + to_call
+    }
+
+    // The most the active player can win or lose: their stack, unless every
+    // opponent still holding cards has less behind
+    pub fn effective_stack(&self) -> usize {
+        let active = match self.active_player_index {
+            Some(active) => active,
+            None => return 0,
+        };
+        let biggest_opponent = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(seat, (_, state))| *seat != active && state.hole_cards().is_some())
+            .map(|(_, (player, _))| player.chips)
+            .max()
+            .unwrap_or_default();
+        core::cmp::min(self.players[active].0.chips, biggest_opponent)
+    }
+
+    // Whether anyone still has a decision to make. It's over once everyone
+    // else has folded, or when the players with chips left can't bet into
+    // each other because only one of them has anything behind and they've
+    // nothing to call.
+    pub fn action_is_on(&self) -> bool {
+        let active = match self.active_player_index {
+            Some(active) => active,
+            None => return false,
+        };
+        if self.players_still_in().len() < 2 {
+            return false;
+        }
+        let with_chips = self
+            .players
+            .iter()
+            .filter(|(player, state)| state.hole_cards().is_some() && player.chips > 0)
+            .count();
+        with_chips > 1 || self.to_call_for(active) > 0
+    }
+
+    fn to_call_for(&self, seat: usize) -> usize {
+        let current_bet = self.street_bets.iter().max().cloned().unwrap_or_default();
+        core::cmp::min(
+            current_bet - self.street_bets[seat],
+            self.players[seat].0.chips,
+        )
+    }
+
+    // Players who haven't folded, which includes anyone all in. Busted
+    // players never make it into the hand in the first place.
+    pub fn players_still_in(&self) -> Vec<&Player> {
+        self.players
+            .iter()
+            .filter(|(_, state)| {
+                !matches!(
+                    state,
+                    PlayerState::Folded | PlayerState::Mucked | PlayerState::SittingOut
+                )
+            })
+            .map(|(player, _)| player)
+            .collect()
+    }
+
+    pub fn folded_players(&self) -> Vec<&Player> {
+        self.players_with_state(|state| *state == PlayerState::Folded)
+    }
+
+    // Anyone who can still win the pot: everyone holding cards, including
+    // all in players and those whose cards are tabled
+    pub fn eligible_winners(&self) -> Vec<&Player> {
+        self.players_with_state(|state| state.hole_cards().is_some())
+    }
+
+    pub fn players_with_state(&self, state: fn(&PlayerState) -> bool) -> Vec<&Player> {
+        self.players
+            .iter()
+            .filter(|(_, player_state)| state(player_state))
+            .map(|(player, _)| player)
+            .collect()
+    }
+
+    // Every player still holding cards with their best hand, strongest first
+    pub fn showdown(&self) -> Vec<(&Player, HandValues)> {
+        let mut hands: Vec<(&Player, u32, HandValues)> = self
+            .players
+            .iter()
+            .filter_map(|(player, state)| {
+                let cards = self.cards_for(state.hole_cards()?);
+                Some((player, hand_score(&cards), evaluate_hand(&cards)))
+            })
+            .collect();
+
+        hands.sort_by_key(|(_, score, _)| core::cmp::Reverse(*score));
+        hands
+            .into_iter()
+            .map(|(player, _, value)| (player, value))
+            .collect()
+    }
+
+    fn cards_for(&self, hole: &HoleCards) -> Vec<Card> {
+        let mut cards = self.community_cards.clone();
+        cards.push(hole.0.clone());
+        cards.push(hole.1.clone());
+        cards
+    }
+
+    // At showdown the losing players throw their cards away unseen, leaving
+    // only the winning hands face up
+    pub fn muck_losing_hands(&mut self) {
+        let winners = self.winning_seats();
+        for (i, (_, state)) in self.players.iter_mut().enumerate() {
+            if let Some(hole) = state.hole_cards() {
+                if !winners.contains(&i) {
+                    self.discard_pile
+                        .extend(vec![hole.0.clone(), hole.1.clone()]);
+                    *state = PlayerState::Mucked;
+                }
+            }
+        }
+    }
+
+    // Seats holding the best hand, more than one when the pot is split
+    fn winning_seats(&self) -> Vec<usize> {
+        let scores: Vec<(usize, u32)> = self
+            .players
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, state))| {
+                let hole = state.hole_cards()?;
+                Some((i, hand_score(&self.cards_for(hole))))
+            })
+            .collect();
+
+        let best = scores.iter().map(|(_, score)| *score).max();
+        scores
+            .iter()
+            .filter(|(_, score)| Some(*score) == best)
+            .map(|(i, _)| *i)
+            .collect()
+    }
+
+    // Splits the pot between the best hands still holding cards, odd chips
+    // going to the earliest seats
+    fn award_pot(&mut self) {
+        let winners = self.winning_seats();
+        if winners.is_empty() {
+            return;
+        }
+
+        let share = self.pot / winners.len();
+        let odd_chips = self.pot % winners.len();
+        for (n, i) in winners.into_iter().enumerate() {
+            let won = share + if n < odd_chips { 1 } else { 0 };
+            self.players[i].0.add_chips(won);
+            self.log.winners.push((self.players[i].0.name.clone(), won));
+        }
+        self.pot = 0;
+    }
+
+    // Hands the action to the next player still holding cards
+    fn advance_action(&mut self, from: usize) {
+        if let PlayerState::Active(hole) = &self.players[from].1 {
+            self.players[from].1 = PlayerState::Dealt(hole.clone());
+        }
+
+        let count = self.players.len();
+        for offset in 1..=count {
+            let seat = (from + offset) % count;
+            let state = &mut self.players[seat].1;
+            if let PlayerState::Dealt(hole) = state {
+                *state = PlayerState::Active(hole.clone());
+                self.active_player_index = Some(seat);
+                return;
+            }
+        }
+        self.active_player_index = None;
+    }
+}
+
+// Players without any chips sit the hand out
+#[must_use = "the returned Hand contains the updated game state"]
+pub fn new_hand(players: Vec<Player>, deck: Deck) -> Hand {
+    let players: Vec<Player> = players
+        .into_iter()
+        .filter(Player::is_eligible_to_play)
+        .collect();
+    Hand {
+        street_bets: vec![0; players.len()],
+        chips_committed_this_hand: vec![0; players.len()],
+        active_player_index: None,
+        discard_pile: vec![],
+        chips_in_play: players.iter().map(|player| player.chips).sum(),
+        cards_in_play: deck.len(),
+        players: players
+            .into_iter()
+            .map(|player| (player, PlayerState::WaitingToBeDealt))
+            .collect(),
+        pot: 0,
+        deck,
+        config: GameConfig::default(),
+        community_cards: vec![],
+        log: GameLog::default(),
+    }
+}
+
+// Every player puts `amount` into the pot before the cards are dealt, going
+// all in if they can't cover it
+pub fn post_antes(mut hand: Hand, amount: usize) -> Result<Hand, PokerError> {
+    if hand.is_dealt() {
+        return Err(PokerError::AlreadyDealt);
+    }
+
+    for i in 0..hand.players.len() {
+        if hand.players[i].1 == PlayerState::WaitingToBeDealt {
+            hand.post_forced_bet(i, amount);
+        }
+    }
+    Ok(hand)
+}
+
+// Once everyone is all in the rest of the board can be run out more than
+// once. Each runout completes the board from the cards left in the deck and
+// plays for an equal share of the pot, the first runout taking any odd chips.
+pub fn run_it(hand: Hand, times: usize, rng: &mut impl rand::Rng) -> Vec<Hand> {
+    let needed = 5 - core::cmp::min(hand.community_cards.len(), 5);
+    let mut remaining = hand.deck.clone();
+    remaining.0.shuffle(rng);
+
+    (0..times)
+        .map(|run| {
+            let mut runout = hand.clone();
+            let cards = remaining
+                .deal_n(core::cmp::min(needed, remaining.len()))
+                .unwrap_or_default();
+            runout.deck.0.retain(|card| !cards.contains(card));
+            runout.community_cards.extend(cards);
+            runout.pot = hand.pot / times + if run < hand.pot % times { 1 } else { 0 };
+            runout.chips_in_play -= hand.pot - runout.pot;
+            runout.award_pot();
+            runout
+        })
+        .collect()
+}
+
+pub fn run_it_twice(hand: Hand, rng: &mut impl rand::Rng) -> (Hand, Hand) {
+    let mut runouts = run_it(hand, 2, rng);
+    let second = runouts.pop().expect("two runouts");
+    let first = runouts.pop().expect("two runouts");
+    (first, second)
+}
+
+// Panics if the hand cannot be dealt, see Hand::deal
+#[deprecated(note = "use Hand::deal instead")]
+#[must_use = "the returned Hand contains the updated game state"]
+pub fn deal(hand: Hand) -> Hand {
+    hand.deal().expect("unable to deal hand")
+}
+
+// Panics if the move cannot be played, see Hand::play
+#[deprecated(note = "use Hand::play instead")]
+#[must_use = "the returned Hand contains the updated game state"]
+pub fn play(hand: Hand, mv: PlayerMove) -> Hand {
+    hand.play(mv).expect("unable to play move")
+}
+
+// Game Configuration
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BettingStructure {
+    #[default]
+    NoLimit,
+    PotLimit,
+    FixedLimit,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameConfig {
+    small_blind: usize,
+    big_blind: usize,
+    ante: usize,
+    structure: BettingStructure,
+    max_players: usize,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            small_blind: 0,
+            big_blind: 0,
+            ante: 0,
+            structure: BettingStructure::NoLimit,
+            max_players: MAX_PLAYERS,
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn builder() -> GameConfigBuilder {
+        GameConfigBuilder::default()
+    }
+
+    pub fn small_blind(&self) -> usize {
+        self.small_blind
+    }
+
+    pub fn big_blind(&self) -> usize {
+        self.big_blind
+    }
+
+    pub fn ante(&self) -> usize {
+        self.ante
+    }
+
+    pub fn structure(&self) -> BettingStructure {
+        self.structure
+    }
+
+    pub fn max_players(&self) -> usize {
+        self.max_players
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GameConfigBuilder {
+    config: GameConfig,
+}
+
+impl GameConfigBuilder {
+    pub fn small_blind(mut self, amount: usize) -> Self {
+        self.config.small_blind = amount;
+        self
+    }
+
+    pub fn big_blind(mut self, amount: usize) -> Self {
+        self.config.big_blind = amount;
+        self
+    }
+
+    pub fn ante(mut self, amount: usize) -> Self {
+        self.config.ante = amount;
+        self
+    }
+
+    pub fn structure(mut self, structure: BettingStructure) -> Self {
+        self.config.structure = structure;
+        self
+    }
+
+    pub fn max_players(mut self, max_players: usize) -> Self {
+        self.config.max_players = max_players;
+        self
+    }
+
+    pub fn build(self) -> GameConfig {
+        self.config
+    }
+}
+
+#[cfg(feature = "std")]
+fn default_shuffler() -> Box<dyn Shuffler> {
+    Box::new(ThreadRngShuffler)
+}
+
+// Without std there's no thread_rng, so games shuffle with a fixed seed
+// unless they're given a shuffler of their own
+#[cfg(not(feature = "std"))]
+fn default_shuffler() -> Box<dyn Shuffler> {
+    Box::new(SeededShuffler { seed: 0 })
+}
+
+pub struct Game {
+    players: Vec<Player>,
+    config: GameConfig,
+    hands_played: usize,
+    shuffler: Box<dyn Shuffler>,
+}
+
+impl Game {
+    pub fn new(players: Vec<Player>, config: GameConfig) -> Game {
+        Game {
+            players,
+            config,
+            hands_played: 0,
+            shuffler: default_shuffler(),
+        }
+    }
+
+    pub fn with_shuffler(self, shuffler: Box<dyn Shuffler>) -> Game {
+        Game { shuffler, ..self }
+    }
+
+    // Shuffles the deck and starts a hand with the antes and blinds already
+    // in the pot. The last two seats post the small and big blind, so the
+    // first seat acts first.
+    pub fn new_hand(&mut self, mut deck: Deck) -> Result<Hand, PokerError> {
+        if self.players.len() > self.config.max_players {
+            return Err(PokerError::TooManyPlayers {
+                max: self.config.max_players,
+                got: self.players.len(),
+            });
+        }
+
+        self.hands_played += 1;
+        self.shuffler.shuffle(&mut deck);
+        let mut hand = new_hand(self.players.clone(), deck);
+        hand.config = self.config.clone();
+        hand.log.hand_number = self.hands_played;
+
+        hand = post_antes(hand, self.config.ante)?;
+
+        let count = hand.players.len();
+        if count >= 2 {
+            hand.post_blind(count - 2, self.config.small_blind);
+            hand.post_blind(count - 1, self.config.big_blind);
+        }
+
+        Ok(hand)
+    }
+
+    // Carries the stacks from a finished hand over to the next one. Players
+    // who were left out of the hand keep what they had.
+    pub fn finish_hand(&mut self, hand: &Hand) {
+        for player in self.players.iter_mut() {
+            if let Some((played, _)) = hand.players.iter().find(|(p, _)| p.name == player.name) {
+                if played.chips > player.chips {
+                    player.add_chips(played.chips - player.chips);
+                } else {
+                    player
+                        .remove_chips(player.chips - played.chips)
+                        .expect("never removes more than the player has");
+                }
+            }
+        }
+    }
+}
+
+// No-Limit Hold'em
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GamePhase {
+    NotStarted,
+    PreFlop,
+    Flop,
+    Turn,
+    River,
+    Showdown,
+    Complete,
+}
+
+// Runs a hand from the deal through to the pot being awarded, one phase at
+// a time, so the streets can't be dealt out of order
+#[derive(Debug, Clone)]
+pub struct StateMachine {
+    hand: Hand,
+    phase: GamePhase,
+}
+
+impl StateMachine {
+    pub fn new(hand: Hand) -> StateMachine {
+        StateMachine {
+            hand,
+            phase: GamePhase::NotStarted,
+        }
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        self.phase
+    }
+
+    pub fn hand(&self) -> &Hand {
+        &self.hand
+    }
+
+    // A move is played by the active player during a betting round, while
+    // None moves on to the next phase. The hand is left as it was when the
+    // transition isn't allowed.
+    pub fn transition(&mut self, mv: Option<PlayerMove>) -> Result<GamePhase, PokerError> {
+        let hand = self.hand.clone();
+        let (hand, phase) = match (self.phase, mv) {
+            (GamePhase::NotStarted, None) => (hand.deal()?, GamePhase::PreFlop),
+            (
+                phase @ (GamePhase::PreFlop | GamePhase::Flop | GamePhase::Turn | GamePhase::River),
+                Some(mv),
+            ) => {
+                let hand = hand.play(mv)?;
+                match hand.players_still_in().as_slice() {
+                    [winner] => {
+                        let winner = winner.name.clone();
+                        (hand.fold_all_but(&winner)?, GamePhase::Complete)
+                    }
+                    _ => (hand, phase),
+                }
+            }
+            (GamePhase::PreFlop, None) => (hand.deal_flop()?, GamePhase::Flop),
+            (GamePhase::Flop, None) => (hand.deal_turn()?, GamePhase::Turn),
+            (GamePhase::Turn, None) => (hand.deal_river()?, GamePhase::River),
+            (GamePhase::River, None) => (hand, GamePhase::Showdown),
+            (GamePhase::Showdown, None) => {
+                let mut hand = hand;
+                hand.muck_losing_hands();
+                hand.award_pot();
+                (hand, GamePhase::Complete)
+            }
+            (phase, _) => return Err(PokerError::IllegalTransition(phase)),
+        };
+
+        self.hand = hand;
+        self.phase = phase;
+        Ok(phase)
+    }
+}
+
+// Seven Card Stud
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StudCard {
+    pub card: Card,
+    pub face_up: bool,
+}
+
+pub const STUD_CARDS: usize = 7;
+
+#[derive(Clone)]
+pub struct StudHand {
+    players: Vec<Player>,
+    player_cards: Vec<Vec<StudCard>>,
+    folded: Vec<bool>,
+    deck: Deck,
+}
+
+impl StudHand {
+    pub fn player_cards(&self) -> &[Vec<StudCard>] {
+        &self.player_cards
+    }
+
+    pub fn fold(mut self, player_name: &str) -> Result<StudHand, PokerError> {
+        let seat = self
+            .players
+            .iter()
+            .position(|player| player.name == player_name)
+            .ok_or_else(|| PokerError::PlayerNotFound(player_name.to_owned()))?;
+        self.folded[seat] = true;
+        Ok(self)
+    }
+}
+
+#[must_use = "the returned StudHand contains the updated game state"]
+pub fn new_stud_hand(players: Vec<Player>, deck: Deck) -> StudHand {
+    StudHand {
+        player_cards: vec![vec![]; players.len()],
+        folded: vec![false; players.len()],
+        players,
+        deck,
+    }
+}
+
+// Deals the next street: one card to every player still in. The first two
+// cards and the seventh are dealt face down, the rest face up. Nothing more
+// is dealt once players have all seven cards or the deck runs out.
+#[must_use = "the returned StudHand contains the updated game state"]
+pub fn deal_stud_round(mut hand: StudHand) -> StudHand {
+    for (cards, folded) in hand.player_cards.iter_mut().zip(hand.folded.iter()) {
+        if *folded || cards.len() >= STUD_CARDS || hand.deck.is_empty() {
+            continue;
+        }
+        let face_up = !matches!(cards.len(), 0 | 1 | 6);
+        cards.push(StudCard {
+            card: hand.deck.0.remove(0),
+            face_up,
+        });
+    }
+    hand
+}
+
+// Five Card Draw
+
+pub const DRAW_CARDS: usize = 5;
+
+// The deck and discards are only needed by draw_round, which needs std
+#[derive(Clone)]
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub struct DrawHand {
+    players: Vec<Player>,
+    player_cards: Vec<Vec<Card>>,
+    deck: Deck,
+    discard_pile: Vec<Card>,
+}
+
+impl DrawHand {
+    pub fn player_cards(&self) -> &[Vec<Card>] {
+        &self.player_cards
+    }
+
+    // Every player's five cards, best hand first
+    pub fn showdown(&self) -> Vec<(&Player, HandValues)> {
+        let mut hands: Vec<(&Player, u32, HandValues)> = self
+            .players
+            .iter()
+            .zip(self.player_cards.iter())
+            .map(|(player, cards)| (player, hand_score(cards), evaluate_hand(cards)))
+            .collect();
+
+        hands.sort_by_key(|(_, score, _)| core::cmp::Reverse(*score));
+        hands
+            .into_iter()
+            .map(|(player, _, value)| (player, value))
+            .collect()
+    }
+}
+
+// Deals five cards to each player, one at a time
+pub fn deal_draw_hand(players: Vec<Player>, mut deck: Deck) -> Result<DrawHand, PokerError> {
+    let need = DRAW_CARDS * players.len();
+    if deck.len() < need {
+        return Err(PokerError::InsufficientCards {
+            need,
+            have: deck.len(),
+        });
+    }
+
+    let mut player_cards = vec![vec![]; players.len()];
+    for _ in 0..DRAW_CARDS {
+        for cards in player_cards.iter_mut() {
+            cards.push(deck.0.remove(0));
+        }
+    }
+    Ok(DrawHand {
+        players,
+        player_cards,
+        deck,
+        discard_pile: vec![],
+    })
+}
+
+// Each player throws away the cards in `draws` and is dealt the same number
+// from the deck, in seat order. Players not in `draws` stand pat.
+#[cfg(feature = "std")]
+pub fn draw_round(
+    mut hand: DrawHand,
+    draws: HashMap<String, Vec<Card>>,
+) -> Result<DrawHand, PokerError> {
+    if let Some(name) = draws
+        .keys()
+        .find(|name| !hand.players.iter().any(|player| player.name == **name))
+    {
+        return Err(PokerError::PlayerNotFound(name.clone()));
+    }
+    let need: usize = draws.values().map(Vec::len).sum();
+    if hand.deck.len() < need {
+        return Err(PokerError::InsufficientCards {
+            need,
+            have: hand.deck.len(),
+        });
+    }
+
+    for (player, cards) in hand.players.iter().zip(hand.player_cards.iter_mut()) {
+        let discards = match draws.get(&player.name) {
+            Some(discards) => discards,
+            None => continue,
+        };
+        for discard in discards {
+            let position = cards
+                .iter()
+                .position(|card| card == discard)
+                .ok_or_else(|| PokerError::CardNotHeld(discard.clone()))?;
+            cards[position] = hand.deck.0.remove(0);
+            hand.discard_pile.push(discard.clone());
+        }
+    }
+    Ok(hand)
+}
+
+// Game Log
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionRecord {
+    pub street: Deal,
+    pub player: String,
+    pub action: PlayerMove,
+    pub pot_before: usize,
+    pub pot_after: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameLog {
+    pub hand_number: usize,
+    pub actions: Vec<ActionRecord>,
+    pub winners: Vec<(String, usize)>,
+    // The deck the hole cards were dealt from, top card first
+    pub deck: Deck,
+    // What each player had when the cards were dealt, forced bets included
+    pub starting_stacks: Vec<(String, usize)>,
+}
+
+impl GameLog {
+    // One JSON object per line, one line per action, e.g.
+    // {"hand":1,"street":"Flop","player":"Will","action":{"Bet":3},"pot_before":0,"pot_after":3}
+    #[cfg(feature = "std")]
+    pub fn write_to_writer(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        for record in self.actions.iter() {
+            let action = match record.action {
+                PlayerMove::Fold => json_string("Fold"),
+                PlayerMove::Check => json_string("Check"),
+                PlayerMove::Bet(amount) => format!("{{\"Bet\":{}}}", amount),
+                PlayerMove::Call(amount) => format!("{{\"Call\":{}}}", amount),
+                PlayerMove::Raise(amount) => format!("{{\"Raise\":{}}}", amount),
+                PlayerMove::Discard(ref cards) => format!(
+                    "{{\"Discard\":[{}]}}",
+                    cards
+                        .iter()
+                        .map(|card| json_string(&card.short_name()))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                ),
+            };
+            writeln!(
+                w,
+                "{{\"hand\":{},\"street\":{},\"player\":{},\"action\":{},\"pot_before\":{},\"pot_after\":{}}}",
+                self.hand_number,
+                json_string(&format!("{:?}", record.street)),
+                json_string(&record.player),
+                action,
+                record.pot_before,
+                record.pot_after,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Quotes and escapes a string for use as a JSON value
+#[cfg(feature = "std")]
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+// Statistics
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PlayerStats {
+    pub hands_played: usize,
+    pub hands_won: usize,
+    pub total_chips_won: usize,
+    // Hands where the player chose to put chips in before the flop
+    pub vpip_count: usize,
+    // Hands where the player raised before the flop
+    pub pfr_count: usize,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct StatTracker {
+    stats: HashMap<String, PlayerStats>,
+}
+
+#[cfg(feature = "std")]
+impl StatTracker {
+    pub fn new() -> StatTracker {
+        StatTracker::default()
+    }
+
+    // Anyone who acted or won is counted as having played the hand. Blinds
+    // aren't logged as actions so they don't count towards VPIP.
+    pub fn record_hand(&mut self, log: &GameLog) {
+        let mut players: Vec<&str> = vec![];
+        for name in log
+            .actions
+            .iter()
+            .map(|record| record.player.as_str())
+            .chain(log.winners.iter().map(|(name, _)| name.as_str()))
+        {
+            if !players.contains(&name) {
+                players.push(name);
+            }
+        }
+
+        for name in players {
+            let preflop = |predicate: fn(&PlayerMove) -> bool| {
+                log.actions.iter().any(|record| {
+                    record.player == name
+                        && record.street == Deal::Hole
+                        && predicate(&record.action)
+                })
+            };
+            let stats = self.stats.entry(name.to_owned()).or_default();
+            stats.hands_played += 1;
+            if preflop(|mv| {
+                matches!(
+                    mv,
+                    PlayerMove::Bet(_) | PlayerMove::Call(_) | PlayerMove::Raise(_)
+                )
+            }) {
+                stats.vpip_count += 1;
+            }
+            if preflop(|mv| matches!(mv, PlayerMove::Raise(_))) {
+                stats.pfr_count += 1;
+            }
+        }
+
+        for (name, won) in log.winners.iter() {
+            let stats = self.stats.entry(name.clone()).or_default();
+            stats.total_chips_won += won;
+        }
+        // A player can collect more than one pot, but they've still only won
+        // the hand once
+        let mut winners: Vec<&String> = log.winners.iter().map(|(name, _)| name).collect();
+        winners.sort();
+        winners.dedup();
+        for name in winners {
+            self.stats.entry(name.clone()).or_default().hands_won += 1;
+        }
+    }
+
+    pub fn stats(&self, name: &str) -> Option<&PlayerStats> {
+        self.stats.get(name)
+    }
+
+    // The share of hands the player voluntarily put chips in, 0.0 for a
+    // player we haven't seen play
+    pub fn vpip_rate(&self, name: &str) -> f64 {
+        match self.stats(name) {
+            Some(stats) if stats.hands_played > 0 => {
+                stats.vpip_count as f64 / stats.hands_played as f64
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+// Odds
+
+// The share of the final pot the active player has to put in to call, or 0.0
+// when there is nothing to call
+pub fn pot_odds(hand: &Hand) -> f64 {
+    match hand.bet_to_call() {
+        0 => 0.0,
+        call => call as f64 / (hand.pot + call) as f64,
+    }
+}
+
+// The rule of two and four: with two cards to come each out is worth about
+// 4%, with one card to come about 2%. Outs only make sense once there is a
+// flop, so this is 0.0 before the flop and after the river.
+pub fn implied_odds_estimate(hand: &Hand, outs: usize) -> f64 {
+    let per_out = match hand.current_street() {
+        Deal::Flop => 0.04,
+        Deal::Turn => 0.02,
+        Deal::Hole | Deal::River => 0.0,
+    };
+    f64::min(outs as f64 * per_out, 1.0)
+}
+
+// What a call wins on average: the pot when it wins, less the call when it
+// loses. A player can't lose more than their stack so the call is capped.
+pub fn expected_value(
+    player: &Player,
+    call_amount: usize,
+    pot_size: usize,
+    win_probability: f64,
+) -> f64 {
+    let call = core::cmp::min(call_amount, player.chips) as f64;
+    win_probability * pot_size as f64 - (1.0 - win_probability) * call
+}
+
+// Unused but potentially interesting follow-on structures
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PlayerPosition {
+    Button,
+    SmallBlind,
+    BigBlind,
+    UnderTheGun,
+    MiddlePosition,
+    Cutoff,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Deal {
+    Hole,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Deal {
+    pub fn next(&self) -> Option<Deal> {
+        match self {
+            Deal::Hole => Some(Deal::Flop),
+            Deal::Flop => Some(Deal::Turn),
+            Deal::Turn => Some(Deal::River),
+            Deal::River => None,
+        }
+    }
+
+    // New cards on the board
+    pub fn community_card_count(&self) -> usize {
+        match self {
+            Deal::Hole => 0,
+            Deal::Flop => 3,
+            Deal::Turn | Deal::River => 1,
+        }
+    }
+
+    // Cards thrown away unseen before the street is dealt
+    pub fn burn_count(&self) -> usize {
+        match self {
+            Deal::Hole => 0,
+            Deal::Flop | Deal::Turn | Deal::River => 1,
+        }
+    }
+
+    // Cards taken off the deck, which before the flop is two for every player
+    // and afterwards includes the burn card
+    pub fn total_cards_dealt_to_deck(&self, player_count: usize) -> usize {
+        match self {
+            Deal::Hole => 2 * player_count,
+            _ => self.burn_count() + self.community_card_count(),
+        }
+    }
+}
+
+// Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use std::collections::{HashMap, HashSet};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn suits_display_as_icons_and_ranks_as_text() {
+        // Hint: The format! macro makes use of the Display Trait.
+        // How can we display different values for different enumerations?
+        assert_eq!("(♥, Ace)", format!("{}", Card(Suit::Heart, Rank::Ace)));
+        assert_eq!("(♦, Ten)", format!("{}", Card(Suit::Diamond, Rank::Ten)));
+        assert_eq!("(♣, Ace)", format!("{}", Card(Suit::Club, Rank::Ace)));
+        assert_eq!("(♠, Jack)", format!("{}", Card(Suit::Spade, Rank::Jack)));
+    }
+
+    #[test]
+    fn cards_have_unicode_playing_card_symbols() {
+        assert_eq!(Card(Suit::Spade, Rank::Ace).unicode_symbol(), '🂡');
+        assert_eq!(Card(Suit::Spade, Rank::Ten).unicode_symbol(), '🂪');
+        assert_eq!(Card(Suit::Heart, Rank::Ace).unicode_symbol(), '🂱');
+        assert_eq!(Card(Suit::Heart, Rank::Jack).unicode_symbol(), '🂻');
+        assert_eq!(Card(Suit::Diamond, Rank::Queen).unicode_symbol(), '🃍');
+        assert_eq!(Card(Suit::Diamond, Rank::Two).unicode_symbol(), '🃂');
+        assert_eq!(Card(Suit::Club, Rank::King).unicode_symbol(), '🃞');
+        assert_eq!(Card(Suit::Club, Rank::Seven).unicode_symbol(), '\u{1F0D7}');
+    }
+
+    #[test]
+    fn ranks_display_as_abbreviations() {
+        let displayed: Vec<String> = Rank::ALL.iter().map(|rank| rank.to_string()).collect();
+        assert_eq!(
+            displayed,
+            vec!["A", "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K"]
+        );
+    }
+
+    #[test]
+    fn rank_gaps_count_aces_high_or_low() {
+        assert_eq!(Rank::Jack.gap_to(&Rank::Nine), 2);
+        assert_eq!(Rank::Nine.gap_to(&Rank::Jack), 2);
+        assert_eq!(Rank::Ace.gap_to(&Rank::King), 1);
+        assert_eq!(Rank::Ace.gap_to(&Rank::Two), 12);
+        assert_eq!(Rank::Ace.gap_to_ace_low(&Rank::Two), 1);
+        assert_eq!(Rank::Ace.gap_to_ace_low(&Rank::King), 12);
+        assert_eq!(Rank::Jack.gap_to_ace_low(&Rank::Nine), 2);
+        assert_eq!(Rank::Seven.gap_to(&Rank::Seven), 0);
+    }
+
+    #[test]
+    fn ranks_convert_from_numeric_values() {
+        assert_eq!(Rank::try_from(14), Ok(Rank::Ace));
+        assert_eq!(Rank::try_from(13), Ok(Rank::King));
+        assert_eq!(Rank::try_from(10), Ok(Rank::Ten));
+        assert_eq!(Rank::try_from(2), Ok(Rank::Two));
+        for rank in Rank::ALL.iter() {
+            assert_eq!(Rank::try_from(rank.numeric_value()), Ok(*rank));
+        }
+
+        assert_eq!(Rank::try_from(0), Err(InvalidRank(0)));
+        assert_eq!(Rank::try_from(1), Err(InvalidRank(1)));
+        assert_eq!(Rank::try_from(15), Err(InvalidRank(15)));
+    }
+
+    #[test]
+    fn ranks_parse_from_short_and_long_forms() {
+        let long = [
+            "Ace", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack",
+            "Queen", "King",
+        ];
+        for (rank, name) in Rank::ALL.iter().zip(long.iter()) {
+            assert_eq!(rank.to_string().parse::<Rank>(), Ok(*rank));
+            assert_eq!(rank.to_string().to_lowercase().parse::<Rank>(), Ok(*rank));
+            assert_eq!(name.parse::<Rank>(), Ok(*rank));
+            assert_eq!(name.to_uppercase().parse::<Rank>(), Ok(*rank));
+            assert_eq!(
+                rank.to_string().parse::<Rank>().unwrap().to_string(),
+                rank.to_string()
+            );
+        }
+
+        assert_eq!("1".parse::<Rank>(), Err(ParseRankError(s("1"))));
+        assert!("Aces".parse::<Rank>().is_err());
+        assert!("".parse::<Rank>().is_err());
+    }
+
+    #[test]
+    fn suits_parse_from_short_and_long_forms() {
+        let forms = [
+            (
+                Suit::Diamond,
+                ["d", "D", "diamond", "Diamond", "diamonds", "DIAMONDS"],
+            ),
+            (
+                Suit::Heart,
+                ["h", "H", "heart", "Heart", "hearts", "HEARTS"],
+            ),
+            (Suit::Club, ["c", "C", "club", "Club", "clubs", "CLUBS"]),
+            (
+                Suit::Spade,
+                ["s", "S", "spade", "Spade", "spades", "SPADES"],
+            ),
+        ];
+        for (suit, names) in forms.iter() {
+            for name in names.iter() {
+                assert_eq!(name.parse::<Suit>(), Ok(*suit));
+            }
+        }
+
+        assert_eq!("x".parse::<Suit>(), Err(ParseSuitError(s("x"))));
+        assert!("♥".parse::<Suit>().is_err());
+        assert!("".parse::<Suit>().is_err());
+    }
+
+    #[test]
+    fn cards_parse_from_compact_and_verbose_notation() {
+        assert_eq!("Ah".parse(), Ok(Card(Suit::Heart, Rank::Ace)));
+        assert_eq!("td".parse(), Ok(Card(Suit::Diamond, Rank::Ten)));
+        assert_eq!("10c".parse(), Ok(Card(Suit::Club, Rank::Ten)));
+        assert_eq!("Ace of Spades".parse(), Ok(Card(Suit::Spade, Rank::Ace)));
+        assert_eq!(
+            "Xh".parse::<Card>(),
+            Err(ParseCardError::InvalidRank(ParseRankError(s("X"))))
+        );
+        assert_eq!(
+            "Ax".parse::<Card>(),
+            Err(ParseCardError::InvalidSuit(ParseSuitError(s("x"))))
+        );
+
+        assert_eq!("ace OF hearts".parse(), Ok(Card(Suit::Heart, Rank::Ace)));
+        assert_eq!(
+            "İİ of h".parse::<Card>(),
+            Err(ParseCardError::InvalidRank(ParseRankError(s("İİ"))))
+        );
+        assert_eq!(
+            "ẞ of hearts".parse::<Card>(),
+            Err(ParseCardError::InvalidRank(ParseRankError(s("ẞ"))))
+        );
+    }
+
+    #[test]
+    fn decks_parse_from_space_separated_cards() {
+        let deck: Deck = "Ah Kd Qc Js Th".parse().unwrap();
+        assert_eq!(deck.len(), 5);
+        assert_eq!(deck[0], Card(Suit::Heart, Rank::Ace));
+        assert_eq!(deck[1], Card(Suit::Diamond, Rank::King));
+        assert_eq!(deck[2], Card(Suit::Club, Rank::Queen));
+        assert_eq!(deck[3], Card(Suit::Spade, Rank::Jack));
+        assert_eq!(deck[4], Card(Suit::Heart, Rank::Ten));
+
+        assert_eq!(
+            "Ah Kd Ah".parse::<Deck>(),
+            Err(ParseDeckError::DuplicateCard(Card(Suit::Heart, Rank::Ace)))
+        );
+        assert!(matches!(
+            "Ah Kd Zz".parse::<Deck>(),
+            Err(ParseDeckError::InvalidCard(_))
+        ));
+        assert_eq!("".parse::<Deck>(), Ok(Deck::new()));
+    }
+
+    #[test]
+    fn decks_split_into_top_and_bottom() {
+        let (top, bottom) = full_deck().split_at(26).unwrap();
+        assert_eq!(top.len(), 26);
+        assert_eq!(bottom.len(), 26);
+        assert_eq!(top[0], full_deck()[0]);
+        assert_eq!(bottom[0], full_deck()[26]);
+
+        let union: HashSet<Card> = top.into_iter().chain(bottom).collect();
+        let original: HashSet<Card> = full_deck().into_iter().collect();
+        assert_eq!(union, original);
+
+        let (all, none) = simple_deck().split_at(4).unwrap();
+        assert_eq!((all, none), (simple_deck(), Deck::new()));
+        assert_eq!(
+            simple_deck().split_at(5).err(),
+            Some(PokerError::InsufficientCards { need: 5, have: 4 })
+        );
+    }
+
+    #[test]
+    fn decks_interleave_card_by_card() {
+        let (top, bottom) = full_deck().split_at(26).unwrap();
+        let interleaved = top.interleave(bottom);
+        assert_eq!(interleaved.len(), 52);
+        for i in 0..26 {
+            assert_eq!(interleaved[2 * i], full_deck()[i]);
+            assert_eq!(interleaved[2 * i + 1], full_deck()[26 + i]);
+        }
+
+        let (top, bottom) = simple_deck().split_at(1).unwrap();
+        let interleaved = bottom.interleave(top);
+        assert_eq!(interleaved[0], simple_deck()[1]);
+        assert_eq!(interleaved[1], simple_deck()[0]);
+        assert_eq!(interleaved.0[2..], simple_deck().0[2..]);
+        assert_eq!(Deck::new().interleave(simple_deck()), simple_deck());
+    }
+
+    #[test]
+    fn eight_perfect_riffles_restore_a_deck() {
+        let mut deck = full_deck();
+        deck.perfect_riffle_shuffle();
+        assert_eq!(deck[0], full_deck()[0]);
+        assert_eq!(deck[1], full_deck()[26]);
+        for shuffles in 2..=8 {
+            assert_ne!(
+                deck,
+                full_deck(),
+                "restored after {} shuffles",
+                shuffles - 1
+            );
+            deck.perfect_riffle_shuffle();
+        }
+        assert_eq!(deck, full_deck());
+    }
+
+    #[test]
+    fn imperfect_riffles_keep_every_card() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut deck = full_deck();
+        deck.imperfect_riffle_shuffle(&mut rng);
+        assert_ne!(deck, full_deck());
+        let shuffled: HashSet<Card> = deck.into_iter().collect();
+        assert_eq!(shuffled, full_deck().into_iter().collect());
+    }
+
+    #[test]
+    fn decks_count_cards_by_rank_and_suit() {
+        let deck = full_deck();
+        for rank in Rank::ALL.iter() {
+            assert_eq!(deck.count_rank(*rank), 4);
+        }
+        for suit in [Suit::Heart, Suit::Diamond, Suit::Club, Suit::Spade] {
+            assert_eq!(deck.count_suit(suit), 13);
+        }
+
+        let no_hearts: Deck = deck
+            .into_iter()
+            .filter(|card| card.suit() != Suit::Heart)
+            .collect();
+        assert_eq!(no_hearts.count_suit(Suit::Heart), 0);
+        assert_eq!(no_hearts.count_suit(Suit::Spade), 13);
+        assert_eq!(no_hearts.count_rank(Rank::Ace), 3);
+    }
+
+    #[test]
+    fn cards_map_to_distinct_indexes_and_back() {
+        let indexes: HashSet<u8> = full_deck().iter().map(card_to_index).collect();
+        assert_eq!(indexes, (0..52).collect());
+        for card in full_deck() {
+            assert_eq!(index_to_card(card_to_index(&card)), card);
+        }
+
+        assert_eq!(card_to_index(&Card(Suit::Diamond, Rank::Two)), 0);
+        assert_eq!(card_to_index(&Card(Suit::Spade, Rank::Ace)), 51);
+    }
+
+    #[test]
+    fn decks_merge_into_shoes() {
+        let merged = simple_deck().merge(full_deck());
+        assert_eq!(merged.len(), 56);
+        assert_eq!(merged[3], simple_deck()[3]);
+        assert_eq!(merged[4], full_deck()[0]);
+
+        let shoe = Deck::shoe(2);
+        assert_eq!(shoe.len(), 104);
+        let mut counts: HashMap<Card, usize> = HashMap::new();
+        for card in shoe {
+            *counts.entry(card).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 52);
+        assert!(counts.values().all(|&count| count == 2));
+
+        assert_eq!(Deck::shoe(8).len(), 416);
+        assert!(Deck::shoe(0).is_empty());
+    }
+
+    #[test]
+    fn suits_convert_from_single_characters() {
+        let chars = [
+            ('d', Suit::Diamond),
+            ('D', Suit::Diamond),
+            ('h', Suit::Heart),
+            ('H', Suit::Heart),
+            ('c', Suit::Club),
+            ('C', Suit::Club),
+            ('s', Suit::Spade),
+            ('S', Suit::Spade),
+        ];
+        for (c, suit) in chars.iter() {
+            assert_eq!(Suit::try_from(*c), Ok(*suit));
+        }
+
+        for c in ['x', 'A', '1', ' ', '♥'].iter() {
+            assert_eq!(Suit::try_from(*c), Err(InvalidSuit(*c)));
+        }
+    }
+
+    #[test]
+    fn new_hand_results_in_all_players_waiting_and_pot_of_zero() {
+        let hand = new_hand(
+            vec![Player::new("Will", 10), Player::new("Jean", 2)],
+            simple_deck(),
+        );
+
+        assert_eq!(hand.pot, 0);
+        assert_eq!(hand.deck, simple_deck());
+        assert_eq!(hand.players.len(), 2);
+        for player in hand.players.iter() {
+            assert_eq!(player.1, PlayerState::WaitingToBeDealt);
+        }
+    }
+
+    #[test]
+    fn deal_provides_cards_from_deck_and_sets_first_player_active() {
+        let will = Player::new("Will", 10);
+        let hand = new_hand(
+            vec![will.clone(), Player::new("Jean", 2)],
+            // Note: simple_deck just has some aces
+            simple_deck(),
+        );
+
+        let hand = hand.deal().unwrap();
+        assert_eq!(
+            hand.players.first().unwrap().1,
+            PlayerState::Active(HoleCards(
+                simple_deck()[0].clone(),
+                simple_deck()[1].clone()
+            )),
+        );
+        assert_eq!(
+            hand.players.last().unwrap().1,
+            PlayerState::Dealt(HoleCards(
+                simple_deck()[2].clone(),
+                simple_deck()[3].clone()
+            )),
+        );
+    }
+
+    #[test]
+    fn check_leaves_pot_untouched() {
+        let hand = new_hand(
+            vec![Player::new("Will", 10), Player::new("Jean", 2)],
+            simple_deck(),
+        );
+
+        let hand = hand.deal().unwrap().play(PlayerMove::Check).unwrap();
+        assert_eq!(hand.pot, 0);
+
+        assert_eq!(
+            hand.players.first().unwrap().1,
+            PlayerState::Dealt(HoleCards(
+                simple_deck()[0].clone(),
+                simple_deck()[1].clone()
+            )),
+        );
+        assert_eq!(
+            hand.players.last().unwrap().1,
+            PlayerState::Active(HoleCards(
+                simple_deck()[2].clone(),
+                simple_deck()[3].clone()
+            )),
+        );
+    }
+
+    #[test]
+    fn bet_increases_the_pot() {
+        let hand = new_hand(
+            vec![
+                Player::new("Will", 10),
+                Player::new("Jean", 2), // <--- loser
+            ],
+            simple_deck(),
+        );
+
+        let hand = hand.deal().unwrap().play(PlayerMove::Bet(3)).unwrap();
+        assert_eq!(hand.pot, 3);
+
+        assert_eq!(hand.players.first().unwrap().0.chips, 7);
+        assert!(matches!(
+            hand.players.last().unwrap().1,
+            PlayerState::Active(_)
+        ));
+    }
+
+    #[test]
+    fn raise_increases_the_pot() {
+        let hand = new_hand(
+            vec![
+                Player::new("Will", 10),
+                Player::new("Jean", 2), // <--- loser
+            ],
+            simple_deck(),
+        );
+
+        let hand = hand.deal().unwrap().play(PlayerMove::Raise(3)).unwrap();
+        assert_eq!(hand.pot, 3);
+
+        assert_eq!(hand.players.first().unwrap().0.chips, 7);
+        assert!(matches!(
+            hand.players.last().unwrap().1,
+            PlayerState::Active(_)
+        ));
+    }
+
+    #[test]
+    fn fold_sets_the_player_state_to_folded() {
+        let hand = new_hand(
+            vec![
+                Player::new("Will", 10),
+                Player::new("Jean", 2), // <--- loser
+            ],
+            simple_deck(),
+        );
+
+        let hand = hand.deal().unwrap().play(PlayerMove::Fold).unwrap();
+        assert_eq!(hand.players.first().unwrap().1, PlayerState::Folded);
+
+        assert!(matches!(
+            hand.players.last().unwrap().1,
+            PlayerState::Active(_)
+        ));
+    }
+
+    #[test]
+    fn folding_moves_players_out_of_the_hand() {
+        let hand = new_hand(
+            vec![
+                Player::new("Will", 10),
+                Player::new("Jean", 2),
+                Player::new("Bob", 0),
+            ],
+            simple_deck(),
+        );
+        assert_eq!(hand.players_still_in().len(), 2);
+        assert!(hand.folded_players().is_empty());
+
+        let hand = hand.deal().unwrap().play(PlayerMove::Fold).unwrap();
+        assert_eq!(hand.players_still_in(), vec![&hand.players[1].0]);
+        assert_eq!(hand.folded_players(), vec![&hand.players[0].0]);
+    }
+
+    #[test]
+    fn run_it_twice_splits_the_pot_between_runouts() {
+        let hand = Hand {
+            players: vec![
+                (Player::new("Will", 0), PlayerState::Dealt(hole("Kh Kc"))),
+                (Player::new("Jean", 0), PlayerState::Dealt(hole("Qh Qc"))),
+            ],
+            deck: "Qd 3s".parse().unwrap(),
+            pot: 101,
+            config: GameConfig::default(),
+            community_cards: "2c 7d 9h Js".parse::<Deck>().unwrap().0,
+            log: GameLog::default(),
+            street_bets: vec![0; 2],
+            chips_committed_this_hand: vec![0; 2],
+            active_player_index: None,
+            discard_pile: vec![],
+            chips_in_play: 101,
+            cards_in_play: 10,
+        };
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let (first, second) = run_it_twice(hand, &mut rng);
+        for runout in [&first, &second].iter() {
+            assert_eq!(runout.pot, 0);
+            assert_eq!(runout.community_cards.len(), 5);
+            assert_eq!(runout.deck.len(), 1);
+        }
+        assert_ne!(first.community_cards[4], second.community_cards[4]);
+
+        let winnings =
+            |player: usize| first.players[player].0.chips + second.players[player].0.chips;
+        assert_eq!(winnings(0) + winnings(1), 101);
+        assert!(winnings(0) == 50 || winnings(0) == 51);
+        assert!(winnings(1) == 50 || winnings(1) == 51);
+    }
+
+    #[test]
+    fn showdown_ranks_heads_up_hands() {
+        let hand = showdown_hand(&["Ah Kd", "7c 7d"], "7h Ks 2c 9d 3s");
+
+        let showdown = hand.showdown();
+        assert_eq!(showdown.len(), 2);
+        assert_eq!(showdown[0].0.name, "Player 2");
+        assert!(matches!(showdown[0].1, HandValues::ThreeOfAKind(_)));
+        assert_eq!(showdown[1].0.name, "Player 1");
+        assert!(matches!(showdown[1].1, HandValues::Pair(_)));
+    }
+
+    #[test]
+    fn showdown_ranks_multiway_hands_and_skips_folded_players() {
+        let mut hand = showdown_hand(&["Ah Kh", "Qs Js", "2d 2c", "As Ad"], "Th 9h 4h 2s 8c");
+        hand.players[3].1 = PlayerState::Folded;
+
+        let ranked: Vec<&str> = hand
+            .showdown()
+            .iter()
+            .map(|(player, _)| player.name.as_str())
+            .collect();
+        assert_eq!(ranked, vec!["Player 1", "Player 2", "Player 3"]);
+        assert!(matches!(hand.showdown()[0].1, HandValues::Flush(_)));
+        assert!(matches!(hand.showdown()[1].1, HandValues::Straight(_)));
+    }
+
+    #[test]
+    fn mucking_hides_every_losing_hand() {
+        let mut hand = showdown_hand(&["Ah Kd", "7c 7d", "Qs Qd"], "7h Ks 2c 9d 3s");
+        hand.muck_losing_hands();
+
+        let visible: Vec<Option<&HoleCards>> = hand
+            .players
+            .iter()
+            .map(|(_, state)| state.hole_cards())
+            .collect();
+        assert_eq!(visible, vec![None, Some(&hole("7c 7d")), None]);
+        assert_eq!(hand.players[0].1, PlayerState::Mucked);
+        assert_eq!(hand.showdown().len(), 1);
+
+        let mut split = showdown_hand(&["Ah 4d", "Ad 5c"], "Kh Ks Qc Qd Jh");
+        split.muck_losing_hands();
+        assert_eq!(split.showdown().len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn game_log_records_actions_as_json_lines() {
+        let config = GameConfig::builder().small_blind(1).big_blind(2).build();
+        let mut game = Game::new(
+            vec![Player::new("Will", 10), Player::new("Jean \"JJ\"", 10)],
+            config,
+        );
+        game.new_hand(simple_deck()).unwrap();
+
+        let hand = game
+            .new_hand(simple_deck())
+            .unwrap()
+            .deal()
+            .unwrap()
+            .play(PlayerMove::Raise(4))
+            .unwrap()
+            .play(PlayerMove::Fold)
+            .unwrap();
+        assert_eq!(hand.log().hand_number, 2);
+        assert_eq!(hand.log().actions.len(), 2);
+        assert_eq!(hand.log().actions[0].street, Deal::Hole);
+
+        let mut out = vec![];
+        hand.log().write_to_writer(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            concat!(
+                "{\"hand\":2,\"street\":\"Hole\",\"player\":\"Will\",\"action\":{\"Raise\":4},\"pot_before\":3,\"pot_after\":7}\n",
+                "{\"hand\":2,\"street\":\"Hole\",\"player\":\"Jean \\\"JJ\\\"\",\"action\":\"Fold\",\"pot_before\":7,\"pot_after\":7}\n",
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn stat_tracker_accumulates_across_hands() {
+        let record = |street, player: &str, action| ActionRecord {
+            street,
+            player: s(player),
+            action,
+            pot_before: 0,
+            pot_after: 0,
+        };
+        let first = GameLog {
+            hand_number: 1,
+            actions: vec![
+                record(Deal::Hole, "Will", PlayerMove::Raise(4)),
+                record(Deal::Hole, "Jean", PlayerMove::Call(2)),
+                record(Deal::Flop, "Jean", PlayerMove::Bet(6)),
+                record(Deal::Flop, "Will", PlayerMove::Fold),
+            ],
+            winners: vec![(s("Jean"), 15)],
+            ..GameLog::default()
+        };
+        let second = GameLog {
+            hand_number: 2,
+            actions: vec![
+                record(Deal::Hole, "Jean", PlayerMove::Fold),
+                record(Deal::Flop, "Will", PlayerMove::Bet(2)),
+            ],
+            winners: vec![(s("Will"), 3)],
+            ..GameLog::default()
+        };
+
+        let mut tracker = StatTracker::new();
+        tracker.record_hand(&first);
+        tracker.record_hand(&second);
+
+        assert_eq!(
+            tracker.stats("Will"),
+            Some(&PlayerStats {
+                hands_played: 2,
+                hands_won: 1,
+                total_chips_won: 3,
+                vpip_count: 1,
+                pfr_count: 1,
+            })
+        );
+        assert_eq!(tracker.stats("Jean").unwrap().total_chips_won, 15);
+        assert!((tracker.vpip_rate("Jean") - 0.5).abs() < 1e-9);
+        assert!((tracker.vpip_rate("Bob") - 0.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn stat_tracker_counts_a_hand_won_once_however_many_pots() {
+        let log = GameLog {
+            hand_number: 1,
+            winners: vec![(s("Will"), 10), (s("Jean"), 4), (s("Will"), 2)],
+            ..GameLog::default()
+        };
+        let mut tracker = StatTracker::new();
+        tracker.record_hand(&log);
+
+        let will = tracker.stats("Will").unwrap();
+        assert_eq!(will.hands_won, 1);
+        assert_eq!(will.hands_played, 1);
+        assert_eq!(will.total_chips_won, 12);
+        assert_eq!(tracker.stats("Jean").unwrap().hands_won, 1);
+    }
+
+    #[test]
+    fn poker_errors_chain_to_their_source() {
+        use std::error::Error;
+
+        let card_error = "Zh".parse::<Card>().unwrap_err();
+        let error = PokerError::from(card_error.clone());
+        assert_eq!(error, PokerError::InvalidCard(card_error.clone()));
+        assert_eq!(
+            error.source().map(|e| e.to_string()),
+            Some(card_error.to_string())
+        );
+        assert!(card_error.source().is_some());
+        assert!(PokerError::EmptyDeck.source().is_none());
+    }
+
+    #[test]
+    fn pot_odds_compare_the_call_to_the_pot() {
+        let mut hand = showdown_hand(&["Ah Kd", "7c 7d"], "7h Ks 2c");
+        hand.players[0].0.add_chips(100);
+        assert_eq!(pot_odds(&hand), 0.0);
+
+        hand.pot = 100;
+        hand.street_bets = vec![0, 50];
+        assert!((pot_odds(&hand) - 1.0 / 3.0).abs() < 1e-9);
+
+        hand.players[0].0.remove_chips(80).unwrap();
+        assert!((pot_odds(&hand) - 20.0 / 120.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn committed_chips_and_stacks_add_up_to_the_buyins() {
+        let config = GameConfig::builder()
+            .small_blind(1)
+            .big_blind(2)
+            .ante(1)
+            .build();
+        let players: Vec<Player> = [("Will", 100), ("Jean", 50), ("Bob", 3)]
+            .iter()
+            .map(|(name, chips)| Player::new(*name, *chips))
+            .collect();
+        let buyins: usize = players.iter().map(Player::chips).sum();
+
+        let mut game = Game::new(players, config).with_shuffler(Box::new(NoopShuffler));
+        let stacks = |hand: &Hand| hand.players.iter().map(|(p, _)| p.chips).sum::<usize>();
+        let mut committed = vec![];
+        let mut starting_stacks = vec![];
+        for _ in 0..3 {
+            let hand = game
+                .new_hand(full_deck())
+                .unwrap()
+                .deal()
+                .unwrap()
+                .play(PlayerMove::Raise(10))
+                .unwrap()
+                .play(PlayerMove::Call(8))
+                .unwrap();
+            assert_eq!(hand.total_chips_committed(), hand.pot);
+            assert_eq!(hand.total_chips_committed() + stacks(&hand), buyins);
+            committed.push(hand.chips_committed_this_hand.clone());
+
+            let mut hand = hand
+                .deal_flop()
+                .unwrap()
+                .deal_turn()
+                .unwrap()
+                .deal_river()
+                .unwrap();
+            hand.muck_losing_hands();
+            hand.award_pot();
+            assert_eq!(hand.pot, 0);
+            assert_eq!(stacks(&hand), buyins);
+
+            starting_stacks.push(hand.log.starting_stacks.clone());
+            game.finish_hand(&hand);
+            assert_eq!(
+                game.players.iter().map(Player::chips).sum::<usize>(),
+                buyins
+            );
+        }
+
+        assert_eq!(committed, vec![vec![11, 10, 3], vec![12, 11], vec![12, 11]]);
+        // Will wins every hand, knocking Bob out in the first, and each hand
+        // starts from the stacks the last one finished with
+        let stack = |name: &str, chips| (s(name), chips);
+        assert_eq!(
+            starting_stacks,
+            vec![
+                vec![stack("Will", 100), stack("Jean", 50), stack("Bob", 3)],
+                vec![stack("Will", 113), stack("Jean", 40)],
+                vec![stack("Will", 124), stack("Jean", 29)],
+            ]
+        );
+    }
+
+    #[test]
+    fn bet_to_call_is_what_the_active_player_still_owes() {
+        let config = GameConfig::builder().small_blind(5).big_blind(10).build();
+        let players: Vec<Player> = ["Will", "Jean", "Bob"]
+            .iter()
+            .map(|name| Player::new(*name, 100))
+            .collect();
+        let mut game = Game::new(players, config).with_shuffler(Box::new(NoopShuffler));
+        let hand = game.new_hand(full_deck()).unwrap().deal().unwrap();
+        assert_eq!(hand.bet_to_call(), 10);
+
+        // Under the gun raises to 30, leaving the small blind 25 to call
+        let hand = hand.play(PlayerMove::Raise(30)).unwrap();
+        assert_eq!(hand.active_player_index(), Some(1));
+        assert_eq!(hand.bet_to_call(), 25);
+
+        let hand = hand
+            .play(PlayerMove::Call(25))
+            .unwrap()
+            .play(PlayerMove::Call(20))
+            .unwrap()
+            .deal_flop()
+            .unwrap();
+        assert_eq!(hand.bet_to_call(), 0);
+    }
+
+    #[test]
+    fn effective_stacks_are_limited_by_the_biggest_opponent() {
+        let mut hand = showdown_hand(&["Ah Kd", "7c 7d", "2s 3s"], "");
+        for (seat, chips) in [200, 100, 300].iter().enumerate() {
+            hand.players[seat].0.add_chips(*chips);
+        }
+        assert_eq!(hand.effective_stack(), 200);
+
+        hand.players[2].1 = PlayerState::Folded;
+        assert_eq!(hand.effective_stack(), 100);
+
+        hand.active_player_index = None;
+        assert_eq!(hand.effective_stack(), 0);
+    }
+
+    #[test]
+    fn action_is_off_once_the_hand_is_decided() {
+        let players = vec![
+            Player::new("Will", 10),
+            Player::new("Jean", 10),
+            Player::new("Bob", 10),
+        ];
+        let hand = new_hand(players, full_deck()).deal().unwrap();
+        assert!(hand.action_is_on());
+
+        let folded = hand
+            .clone()
+            .play(PlayerMove::Fold)
+            .unwrap()
+            .play(PlayerMove::Fold)
+            .unwrap();
+        assert!(!folded.action_is_on());
+
+        let all_in = hand.play(PlayerMove::Bet(10)).unwrap();
+        assert!(all_in.action_is_on());
+        let all_in = all_in
+            .play(PlayerMove::Call(10))
+            .unwrap()
+            .play(PlayerMove::Call(10))
+            .unwrap();
+        assert!(!all_in.action_is_on());
+    }
+
+    #[test]
+    fn pot_size_raises() {
+        let mut hand = showdown_hand(&["Ah Kd", "7c 7d", "2s 3s"], "7h Ks 2c");
+        // An opening bet of 10 into an empty pot
+        hand.pot = 10;
+        hand.street_bets = vec![0, 0, 10];
+        assert_eq!(hand.pot_size_raise(), 30);
+
+        // 100 in the pot from earlier streets, a bet of 20 and a raise to 60
+        hand.pot = 180;
+        hand.street_bets = vec![20, 60, 0];
+        assert_eq!(hand.pot_size_raise(), 280);
+
+        // Then a re-raise to 280, leaving the second player 220 to call
+        hand.pot = 400;
+        hand.street_bets = vec![20, 60, 280];
+        hand.active_player_index = Some(1);
+        assert_eq!(hand.pot_size_raise(), 280 + 400 + 220);
+    }
+
+    #[test]
+    fn time_banks_are_capped_and_cannot_go_negative() {
+        let players = ["Will", "Jean"]
+            .iter()
+            .map(|name| Player::new(*name, 10))
+            .collect();
+        let hand = new_hand(players, full_deck())
+            .time_bank_extend("Will", 90)
+            .unwrap()
+            .time_bank_extend("Will", 60)
+            .unwrap();
+        assert_eq!(hand.players[0].0.time_bank_seconds(), 120);
+
+        let hand = hand.time_bank_use("Will", 100).unwrap();
+        assert_eq!(hand.players[0].0.time_bank_seconds(), 20);
+        assert_eq!(
+            hand.clone().time_bank_use("Will", 30).err(),
+            Some(PokerError::InsufficientTimeBank { have: 20, need: 30 })
+        );
+        assert_eq!(
+            hand.time_bank_use("Jean", 1).err(),
+            Some(PokerError::InsufficientTimeBank { have: 0, need: 1 })
+        );
+    }
+
+    #[test]
+    fn straddlers_act_last_before_the_flop() {
+        let config = GameConfig::builder().small_blind(1).big_blind(2).build();
+        let players = ["Will", "Jean", "Bob", "Alice"]
+            .iter()
+            .map(|name| Player::new(*name, 100))
+            .collect();
+        let mut game = Game::new(players, config);
+        assert_eq!(
+            game.new_hand(full_deck()).unwrap().straddle("Will").err(),
+            Some(PokerError::StraddleNotAllowed)
+        );
+
+        let hand = game
+            .new_hand(full_deck())
+            .unwrap()
+            .deal()
+            .unwrap()
+            .straddle("Will")
+            .unwrap();
+        assert_eq!(hand.pot, 1 + 2 + 4);
+        assert_eq!(hand.players[0].0.chips, 96);
+        assert_eq!(hand.active_player_index(), Some(1));
+        for name in ["Will", "Jean"] {
+            assert_eq!(
+                hand.clone().straddle(name).err(),
+                Some(PokerError::StraddleNotAllowed)
+            );
+        }
+
+        let hand = hand
+            .play(PlayerMove::Call(4))
+            .unwrap()
+            .play(PlayerMove::Call(3))
+            .unwrap()
+            .play(PlayerMove::Call(2))
+            .unwrap();
+        assert_eq!(hand.active_player_index(), Some(0));
+        assert_eq!(pot_odds(&hand), 0.0);
+
+        // Nobody can straddle once the betting has started
+        let hand = game
+            .new_hand(full_deck())
+            .unwrap()
+            .deal()
+            .unwrap()
+            .play(PlayerMove::Call(2))
+            .unwrap();
+        assert_eq!(
+            hand.straddle("Jean").err(),
+            Some(PokerError::StraddleNotAllowed)
+        );
+    }
+
+    #[test]
+    fn pot_odds_include_the_blinds() {
+        let config = GameConfig::builder().small_blind(25).big_blind(50).build();
+        let players = ["Will", "Jean", "Bob"]
+            .iter()
+            .map(|name| Player::new(*name, 1000))
+            .collect();
+        let hand = Game::new(players, config)
+            .new_hand(full_deck())
+            .unwrap()
+            .deal()
+            .unwrap();
+        assert!((pot_odds(&hand) - 50.0 / 125.0).abs() < 1e-9);
+
+        let hand = hand.play(PlayerMove::Call(50)).unwrap();
+        assert!((pot_odds(&hand) - 25.0 / 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn implied_odds_estimate_uses_the_rule_of_two_and_four() {
+        let flush_draw = |board: &str| showdown_hand(&["As Ks", "Qd Qc"], board);
+
+        assert!((implied_odds_estimate(&flush_draw("7s 2s 9h"), 9) - 0.36).abs() < 1e-9);
+        assert!((implied_odds_estimate(&flush_draw("7s 2s 9h Jd"), 9) - 0.18).abs() < 1e-9);
+        assert_eq!(implied_odds_estimate(&flush_draw(""), 9), 0.0);
+        assert_eq!(implied_odds_estimate(&flush_draw("7s 2s 9h Jd 3c"), 9), 0.0);
+        assert_eq!(implied_odds_estimate(&flush_draw("7s 2s 9h"), 30), 1.0);
+    }
+
+    #[test]
+    fn expected_value_weighs_the_pot_against_the_call() {
+        let will = Player::new("Will", 1000);
+        assert_eq!(expected_value(&will, 100, 100, 0.5), 0.0);
+        assert!((expected_value(&will, 50, 100, 0.4) - 10.0).abs() < 1e-9);
+        assert!(expected_value(&will, 100, 100, 0.25) < 0.0);
+
+        let short = Player::new("Jean", 20);
+        assert!((expected_value(&short, 100, 100, 0.5) - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hand_expected_value_uses_the_players_call() {
+        let mut hand = showdown_hand(&["Ah Kd", "7c 7d"], "7h Ks 2c");
+        hand.players[0].0.add_chips(1000);
+        hand.pot = 150;
+        hand.street_bets = vec![0, 50];
+
+        let will = hand.players[0].0.clone();
+        assert!((hand.expected_value(&will, 0.25) - 0.0).abs() < 1e-9);
+        assert!((hand.expected_value(&will, 0.5) - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stud_rounds_deal_one_card_to_each_player_still_in() {
+        let players = vec![Player::new("Will", 10), Player::new("Jean", 2)];
+        let mut hand = new_stud_hand(players, full_deck());
+        for _ in 0..3 {
+            hand = deal_stud_round(hand);
+        }
+
+        assert_eq!(hand.player_cards()[0].len(), 3);
+        assert_eq!(hand.player_cards()[0][0].card, full_deck()[0]);
+        assert_eq!(hand.player_cards()[1][0].card, full_deck()[1]);
+        let faces: Vec<bool> = hand.player_cards()[1].iter().map(|c| c.face_up).collect();
+        assert_eq!(faces, vec![false, false, true]);
+
+        let mut hand = hand.fold("Jean").unwrap();
+        for _ in 0..6 {
+            hand = deal_stud_round(hand);
+        }
+        assert_eq!(hand.player_cards()[0].len(), STUD_CARDS);
+        assert_eq!(hand.player_cards()[1].len(), 3);
+        let faces: Vec<bool> = hand.player_cards()[0].iter().map(|c| c.face_up).collect();
+        assert_eq!(faces, vec![false, false, true, true, true, true, false]);
+        assert_eq!(hand.deck.len(), 52 - 10);
+
+        assert_eq!(
+            hand.fold("Bob").err(),
+            Some(PokerError::PlayerNotFound(s("Bob")))
+        );
+    }
+
+    #[test]
+    fn transferring_chips_conserves_the_total() {
+        let players = vec![Player::new("Will", 10), Player::new("Jean", 2)];
+        let total = |hand: &Hand| hand.players.iter().map(|(p, _)| p.chips).sum::<usize>();
+
+        let hand = new_hand(players.clone(), simple_deck())
+            .transfer_chips("Will", "Jean", 4)
+            .unwrap();
+        assert_eq!(hand.players[0].0.chips, 6);
+        assert_eq!(hand.players[1].0.chips, 6);
+        assert_eq!(total(&hand), 12);
+
+        assert_eq!(
+            hand.clone().transfer_chips("Jean", "Will", 7).err(),
+            Some(PokerError::InsufficientChips { have: 6, need: 7 })
+        );
+        assert_eq!(
+            new_hand(players, simple_deck())
+                .transfer_chips("Will", "Bob", 1)
+                .err(),
+            Some(PokerError::PlayerNotFound(s("Bob")))
+        );
+        assert_eq!(
+            hand.deal().unwrap().transfer_chips("Will", "Jean", 1).err(),
+            Some(PokerError::AlreadyDealt)
+        );
+    }
+
+    #[test]
+    fn players_without_chips_are_left_out_of_the_hand() {
+        let players = vec![
+            Player::new("Will", 10),
+            Player::new("Jean", 0),
+            Player::new("Bob", 4),
+        ];
+        assert!(!players[1].is_eligible_to_play());
+
+        let hand = new_hand(players, full_deck());
+        let names: Vec<&str> = hand.players.iter().map(|(p, _)| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Will", "Bob"]);
+        assert_eq!(hand.street_bets.len(), 2);
+    }
+
+    #[test]
+    fn players_can_only_remove_chips_they_have() {
+        let mut player = Player::new("Will", 10);
+        player.add_chips(5);
+        assert_eq!(player.remove_chips(15), Ok(()));
+        assert_eq!(player.chips, 0);
+        assert_eq!(
+            player.remove_chips(1),
+            Err(PokerError::InsufficientChips { have: 0, need: 1 })
+        );
+    }
+
+    #[test]
+    fn active_player_index_follows_the_action() {
+        let players = ["Will", "Jean", "Bob"]
+            .iter()
+            .map(|name| Player::new(*name, 10))
+            .collect();
+        let hand = new_hand(players, full_deck());
+        assert_eq!(hand.active_player_index(), None);
+
+        let hand = hand.deal().unwrap();
+        assert_eq!(hand.active_player_index(), Some(0));
+        let hand = hand.play(PlayerMove::Fold).unwrap();
+        assert_eq!(hand.active_player_index(), Some(1));
+        let hand = hand.play(PlayerMove::Check).unwrap();
+        assert_eq!(hand.active_player_index(), Some(2));
+        let hand = hand.play(PlayerMove::Check).unwrap();
+        assert_eq!(hand.active_player_index(), Some(1));
+    }
+
+    #[test]
+    fn community_cards_are_dealt_after_a_burn_card() {
+        let players = ["Will", "Jean"]
+            .iter()
+            .map(|name| Player::new(*name, 10))
+            .collect();
+        let hand = new_hand(players, full_deck());
+        assert_eq!(
+            hand.clone().deal_flop().err(),
+            Some(PokerError::CannotDeal(Deal::Flop))
+        );
+
+        let hand = hand
+            .deal()
+            .unwrap()
+            .play(PlayerMove::Bet(2))
+            .unwrap()
+            .play(PlayerMove::Call(2))
+            .unwrap();
+        assert_eq!(
+            hand.clone().deal_turn().err(),
+            Some(PokerError::CannotDeal(Deal::Turn))
+        );
+
+        let hand = hand.deal_flop().unwrap();
+        assert_eq!(hand.discard_pile, vec![full_deck()[4].clone()]);
+        assert_eq!(hand.board(), &full_deck().0[5..8]);
+        assert_eq!(hand.street_bets, vec![0, 0]);
+        assert_eq!(hand.active_player_index(), Some(0));
+
+        let hand = hand.deal_turn().unwrap().deal_river().unwrap();
+        assert_eq!(hand.current_street(), Deal::River);
+        assert_eq!(hand.discard_pile.len(), 3);
+        assert_eq!(hand.deck.len(), 52 - 4 - 4 - 2 - 2);
+        assert_eq!(
+            hand.deal_river().err(),
+            Some(PokerError::CannotDeal(Deal::River))
+        );
+    }
+
+    #[test]
+    fn state_machine_walks_a_hand_through_every_phase() {
+        let players = ["Will", "Jean"]
+            .iter()
+            .map(|name| Player::new(*name, 10))
+            .collect();
+        let mut machine = StateMachine::new(new_hand(players, full_deck()));
+        assert_eq!(
+            machine.transition(Some(PlayerMove::Check)),
+            Err(PokerError::IllegalTransition(GamePhase::NotStarted))
+        );
+
+        assert_eq!(machine.transition(None), Ok(GamePhase::PreFlop));
+        assert_eq!(
+            machine.transition(Some(PlayerMove::Bet(2))),
+            Ok(GamePhase::PreFlop)
+        );
+        assert_eq!(
+            machine.transition(Some(PlayerMove::Call(2))),
+            Ok(GamePhase::PreFlop)
+        );
+        assert_eq!(machine.transition(None), Ok(GamePhase::Flop));
+        assert_eq!(machine.hand().board().len(), 3);
+        assert_eq!(machine.transition(None), Ok(GamePhase::Turn));
+        assert_eq!(machine.transition(None), Ok(GamePhase::River));
+        assert_eq!(machine.transition(None), Ok(GamePhase::Showdown));
+        assert_eq!(
+            machine.transition(Some(PlayerMove::Check)),
+            Err(PokerError::IllegalTransition(GamePhase::Showdown))
+        );
+        assert_eq!(machine.phase(), GamePhase::Showdown);
+
+        assert_eq!(machine.transition(None), Ok(GamePhase::Complete));
+        assert_eq!(machine.hand().pot, 0);
+        let won: usize = machine.hand().log().winners.iter().map(|w| w.1).sum();
+        assert_eq!(won, 4);
+        assert_eq!(
+            machine.transition(None),
+            Err(PokerError::IllegalTransition(GamePhase::Complete))
+        );
+    }
+
+    #[test]
+    fn state_machine_completes_once_everyone_else_folds() {
+        let players = ["Will", "Jean", "Rory"]
+            .iter()
+            .map(|name| Player::new(*name, 10))
+            .collect();
+        let mut machine = StateMachine::new(new_hand(players, full_deck()));
+        machine.transition(None).unwrap();
+        machine.transition(Some(PlayerMove::Bet(3))).unwrap();
+        assert_eq!(
+            machine.transition(Some(PlayerMove::Fold)),
+            Ok(GamePhase::PreFlop)
+        );
+        assert_eq!(
+            machine.transition(Some(PlayerMove::Fold)),
+            Ok(GamePhase::Complete)
+        );
+        assert_eq!(machine.hand().log().winners, vec![(s("Will"), 3)]);
+        assert_eq!(machine.hand().players[0].0.chips(), 10);
+    }
+
+    #[test]
+    fn validate_reports_every_broken_invariant() {
+        let hand = showdown_hand(&["Ah Kd", "7c 7d"], "7h Ks 2c");
+        assert_eq!(hand.validate(), Ok(()));
+
+        let mut two_active = hand.clone();
+        two_active.players[1].1 = PlayerState::Active(hole("7c 7d"));
+        assert_eq!(
+            two_active.validate(),
+            Err(vec![ValidationError::MultipleActivePlayers(2)])
+        );
+
+        let mut chips = hand.clone();
+        chips.pot = 10;
+        assert_eq!(
+            chips.validate(),
+            Err(vec![ValidationError::ChipsNotConserved {
+                expected: 0,
+                got: 10
+            }])
+        );
+
+        let mut lost = hand.clone();
+        lost.community_cards.pop();
+        assert_eq!(
+            lost.validate(),
+            Err(vec![ValidationError::CardsNotConserved {
+                expected: 7,
+                got: 6
+            }])
+        );
+
+        let mut duplicated = hand;
+        duplicated.community_cards[2] = Card(Suit::Heart, Rank::Ace);
+        duplicated.pot = 1;
+        assert_eq!(
+            duplicated.validate(),
+            Err(vec![
+                ValidationError::ChipsNotConserved {
+                    expected: 0,
+                    got: 1
+                },
+                ValidationError::DuplicateCard(Card(Suit::Heart, Rank::Ace)),
+            ])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn discarding_draws_replacements_from_the_deck() {
+        let players = vec![Player::new("Will", 10), Player::new("Jean", 10)];
+        let deck: Deck = "Ah Kd 7c 7d 2s 3s 4s".parse().unwrap();
+        let hand = new_hand(players, deck).deal().unwrap();
+        let discards = "Ah Kd".parse::<Deck>().unwrap().0;
+
+        let hand = hand.play(PlayerMove::Discard(discards.clone())).unwrap();
+        assert_eq!(hand.players[0].1, PlayerState::Dealt(hole("2s 3s")));
+        assert_eq!(hand.deck, "4s".parse().unwrap());
+        assert_eq!(hand.discard_pile, discards);
+        assert_eq!(hand.active_player_index(), Some(1));
+
+        let mut out = vec![];
+        hand.log().write_to_writer(&mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("\"action\":{\"Discard\":[\"Ah\",\"Kd\"]}"));
+
+        let hand = hand
+            .play(PlayerMove::Discard("7c 7d".parse::<Deck>().unwrap().0))
+            .err();
+        assert_eq!(hand, Some(PokerError::EmptyDeck));
+    }
+
+    #[test]
+    fn draw_poker_players_can_replace_cards() {
+        let mut hand = showdown_hand(&["Ah Kd", "7c 7d"], "");
+        hand.deck = "2c 3c".parse().unwrap();
+        hand.cards_in_play += 2;
+        let ace = Card(Suit::Heart, Rank::Ace);
+        let two = Card(Suit::Club, Rank::Two);
+
+        let swapped = hand
+            .clone()
+            .replace_card("Player 1", ace.clone(), two.clone())
+            .unwrap();
+        assert_eq!(swapped.players[0].1, PlayerState::Active(hole("2c Kd")));
+        assert_eq!(swapped.deck, "3c".parse().unwrap());
+        assert_eq!(swapped.discard_pile, vec![ace.clone()]);
+        assert_eq!(swapped.validate(), Ok(()));
+
+        assert_eq!(
+            hand.clone()
+                .replace_card("Player 1", Card(Suit::Spade, Rank::Ace), two.clone())
+                .err(),
+            Some(PokerError::CardNotHeld(Card(Suit::Spade, Rank::Ace)))
+        );
+        assert_eq!(
+            hand.clone()
+                .replace_card("Player 1", ace.clone(), Card(Suit::Club, Rank::Four))
+                .err(),
+            Some(PokerError::CardNotInDeck(Card(Suit::Club, Rank::Four)))
+        );
+
+        hand.players[1].1 = PlayerState::Folded;
+        assert_eq!(
+            hand.replace_card("Player 2", Card(Suit::Club, Rank::Seven), two)
+                .err(),
+            Some(PokerError::NotInHand(s("Player 2")))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn five_card_draw_hands_are_dealt_drawn_and_evaluated() {
+        let players = vec![Player::new("Will", 10), Player::new("Jean", 10)];
+        let deck: Deck = "Ah 2c Ad 3c Kh 9c Ks 9d 7h Jd Qs Jc 7s".parse().unwrap();
+        assert_eq!(
+            deal_draw_hand(players.clone(), "Ah 2c".parse().unwrap()).err(),
+            Some(PokerError::InsufficientCards { need: 10, have: 2 })
+        );
+
+        let hand = deal_draw_hand(players, deck).unwrap();
+        assert_eq!(
+            hand.player_cards()[0],
+            "Ah Ad Kh Ks 7h".parse::<Deck>().unwrap().0
+        );
+        assert_eq!(
+            hand.player_cards()[1],
+            "2c 3c 9c 9d Jd".parse::<Deck>().unwrap().0
+        );
+
+        let mut draws = HashMap::new();
+        draws.insert(s("Jean"), "2c 3c".parse::<Deck>().unwrap().0);
+        draws.insert(s("Will"), "7h".parse::<Deck>().unwrap().0);
+        let hand = draw_round(hand, draws).unwrap();
+        assert_eq!(
+            hand.player_cards()[0],
+            "Ah Ad Kh Ks Qs".parse::<Deck>().unwrap().0
+        );
+        assert_eq!(
+            hand.player_cards()[1],
+            "Jc 7s 9c 9d Jd".parse::<Deck>().unwrap().0
+        );
+        assert!(hand.deck.is_empty());
+        assert_eq!(hand.discard_pile.len(), 3);
+
+        let showdown = hand.showdown();
+        assert_eq!(showdown[0].0.name, "Will");
+        assert!(matches!(showdown[0].1, HandValues::TwoPairs(_, _)));
+        assert!(matches!(showdown[1].1, HandValues::TwoPairs(_, _)));
+
+        let mut draws = HashMap::new();
+        draws.insert(s("Jean"), vec![Card(Suit::Heart, Rank::Ace)]);
+        assert_eq!(
+            draw_round(hand.clone(), draws).err(),
+            Some(PokerError::InsufficientCards { need: 1, have: 0 })
+        );
+    }
+
+    #[test]
+    fn kicked_players_leave_the_hand_and_forfeit_their_bets() {
+        let players = ["Will", "Jean", "Bob"]
+            .iter()
+            .map(|name| Player::new(*name, 10))
+            .collect();
+        let hand = new_hand(players, full_deck())
+            .deal()
+            .unwrap()
+            .play(PlayerMove::Bet(4))
+            .unwrap();
+        assert_eq!(hand.active_player_index(), Some(1));
+
+        let hand = hand.kick_player("Will").unwrap();
+        assert_eq!(hand.players.len(), 2);
+        assert_eq!(hand.pot, 4);
+        assert_eq!(hand.active_player_index(), Some(0));
+        assert_eq!(hand.players[0].0.name(), "Jean");
+
+        let hand = hand.kick_player("Jean").unwrap();
+        assert_eq!(hand.players.len(), 1);
+        assert_eq!(hand.active_player_index(), Some(0));
+        assert_eq!(hand.players[0].0.name(), "Bob");
+        assert_eq!(hand.discard_pile.len(), 4);
+        assert_eq!(
+            hand.kick_player("Alice").err(),
+            Some(PokerError::PlayerNotFound(s("Alice")))
+        );
+    }
+
+    #[test]
+    fn folding_everyone_else_gives_the_pot_to_the_last_player() {
+        let players = ["Will", "Jean", "Bob"]
+            .iter()
+            .map(|name| Player::new(*name, 10))
+            .collect();
+        let hand = new_hand(players, full_deck())
+            .deal()
+            .unwrap()
+            .play(PlayerMove::Bet(4))
+            .unwrap()
+            .fold_all_but("Jean")
+            .unwrap();
+
+        assert_eq!(hand.pot, 0);
+        assert_eq!(hand.players[1].0.chips, 14);
+        assert_eq!(hand.players_still_in(), vec![&hand.players[1].0]);
+        assert_eq!(hand.folded_players().len(), 2);
+        assert_eq!(hand.active_player_index(), None);
+        assert_eq!(
+            hand.fold_all_but("Alice").err(),
+            Some(PokerError::PlayerNotFound(s("Alice")))
+        );
+    }
+
+    #[test]
+    fn player_accessors() {
+        let will = Player::new("Will", 10);
+        assert_eq!(will.name(), "Will");
+        assert_eq!(will.chips(), 10);
+    }
+
+    #[test]
+    fn players_sort_by_chips_with_the_leader_first() {
+        let player = |name: &str, chips| Player::new(name, chips);
+        let mut players = vec![player("Will", 10), player("Jean", 50), player("Bob", 20)];
+        players.sort_by_chips();
+        let names: Vec<&str> = players.iter().map(Player::name).collect();
+        assert_eq!(names, vec!["Jean", "Bob", "Will"]);
+
+        assert!(player("Jean", 50) < player("Will", 10));
+        assert!(player("Bob", 10) < player("Will", 10));
+
+        let mut patient = player("Will", 10);
+        patient.time_bank_seconds = 30;
+        assert_ne!(patient, player("Will", 10));
+        assert_ne!(
+            patient.partial_cmp(&player("Will", 10)),
+            Some(core::cmp::Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn chips_can_be_added_to_and_taken_from_players() {
+        let will = Player::new("Will", 10);
+        assert_eq!((will.clone() + 100).chips, 110);
+        assert_eq!((will.clone() - 4).chips, 6);
+        assert_eq!(will.clone() + 5 - 5, will);
+    }
+
+    #[test]
+    fn only_players_holding_cards_can_win() {
+        let mut hand = showdown_hand(&["Ah Kd", "7c 7d", "2s 3s", "Qh Jh"], "");
+        hand.players[1].1 = PlayerState::Folded;
+        hand.players[2].1 = PlayerState::Tabled(hole("2s 3s"));
+        hand.players[3].1 = PlayerState::Mucked;
+
+        // Everyone in showdown_hand has no chips left, so they're all in
+        let names: Vec<&str> = hand
+            .eligible_winners()
+            .into_iter()
+            .map(Player::name)
+            .collect();
+        assert_eq!(names, vec!["Player 1", "Player 3"]);
+    }
+
+    #[test]
+    fn players_can_be_filtered_by_state() {
+        let mut hand = showdown_hand(&["Ah Kd", "7c 7d", "2s 3s", "Qh Jh"], "");
+        hand.players[1].1 = PlayerState::Folded;
+        hand.players[2].1 = PlayerState::Tabled(hole("2s 3s"));
+
+        let names = |players: Vec<&Player>| -> Vec<String> {
+            players.into_iter().map(|p| p.name.clone()).collect()
+        };
+        assert_eq!(
+            names(hand.players_with_state(|s| matches!(s, PlayerState::Active(_)))),
+            vec!["Player 1"]
+        );
+        assert_eq!(
+            names(hand.players_with_state(|s| *s == PlayerState::Folded)),
+            vec!["Player 2"]
+        );
+        assert_eq!(
+            names(hand.players_with_state(|s| matches!(s, PlayerState::Tabled(_)))),
+            vec!["Player 3"]
+        );
+        assert!(hand
+            .players_with_state(|s| *s == PlayerState::Mucked)
+            .is_empty());
+    }
+
+    #[test]
+    fn players_can_be_forced_to_show_their_cards() {
+        let players = ["Will", "Jean", "Bob"]
+            .iter()
+            .map(|name| Player::new(*name, 10))
+            .collect();
+        let mut hand = new_hand(players, full_deck());
+        assert_eq!(
+            hand.force_show_cards("Will"),
+            Err(PokerError::NoCardsToShow(s("Will")))
+        );
+
+        let mut hand = hand.deal().unwrap().play(PlayerMove::Fold).unwrap();
+        assert_eq!(
+            hand.force_show_cards("Will"),
+            Err(PokerError::NoCardsToShow(s("Will")))
+        );
+
+        let jean = hand.players[1].1.hole_cards().cloned().unwrap();
+        hand.force_show_cards("Jean").unwrap();
+        assert_eq!(hand.players[1].1, PlayerState::Tabled(jean));
+        assert!(matches!(hand.players[2].1, PlayerState::Active(_)));
+        assert_eq!(hand.players_still_in().len(), 2);
+    }
+
+    #[test]
+    fn players_sitting_out_are_skipped() {
+        let players = ["Will", "Jean", "Bob"]
+            .iter()
+            .map(|name| Player::new(*name, 10))
+            .collect();
+        let hand = new_hand(players, full_deck())
+            .sit_out("Will")
+            .unwrap()
+            .sit_out("Bob")
+            .unwrap()
+            .sit_back_in("Bob")
+            .unwrap();
+        let hand = post_antes(hand, 1).unwrap();
+        assert_eq!(hand.players[0].0.chips, 10);
+        assert_eq!(hand.pot, 2);
+
+        let hand = hand.deal().unwrap();
+        assert_eq!(hand.players[0].1, PlayerState::SittingOut);
+        assert_eq!(hand.active_player_index(), Some(1));
+        assert_eq!(hand.deck.len(), 52 - 4);
+        assert_eq!(hand.players_still_in().len(), 2);
+
+        let hand = hand.play(PlayerMove::Check).unwrap();
+        assert_eq!(hand.active_player_index(), Some(2));
+        let hand = hand.play(PlayerMove::Check).unwrap();
+        assert_eq!(hand.active_player_index(), Some(1));
+
+        assert_eq!(
+            hand.clone().sit_out("Jean").err(),
+            Some(PokerError::AlreadyDealt)
+        );
+        assert_eq!(
+            hand.sit_back_in("Alice").err(),
+            Some(PokerError::PlayerNotFound(s("Alice")))
+        );
+    }
+
+    #[test]
+    fn players_can_only_rebuy_when_not_in_a_hand() {
+        let players = vec![Player::new("Will", 10), Player::new("Jean", 5)];
+
+        let hand = new_hand(players, simple_deck()).rebuy("Jean", 50).unwrap();
+        assert_eq!(hand.players[1].0.chips, 55);
+
+        let hand = hand.deal().unwrap();
+        assert_eq!(
+            hand.clone().rebuy("Will", 50).err(),
+            Some(PokerError::RebuyNotAllowed)
+        );
+        assert_eq!(
+            hand.clone().rebuy("Jean", 50).err(),
+            Some(PokerError::RebuyNotAllowed)
+        );
+
+        let hand = hand
+            .play(PlayerMove::Fold)
+            .unwrap()
+            .rebuy("Will", 50)
+            .unwrap();
+        assert_eq!(hand.players[0].0.chips, 60);
+    }
+
+    #[test]
+    fn invalid_moves_are_rejected() {
+        let players = vec![Player::new("Will", 10), Player::new("Jean", 2)];
+
+        let undealt = new_hand(players.clone(), simple_deck());
+        assert_eq!(
+            undealt.play(PlayerMove::Check).err(),
+            Some(PokerError::NoActivePlayer)
+        );
+
+        let dealt = new_hand(players.clone(), simple_deck()).deal().unwrap();
+        assert_eq!(dealt.deal().err(), Some(PokerError::AlreadyDealt));
+
+        let mut short_deck = simple_deck();
+        short_deck.0.pop();
+        assert_eq!(
+            new_hand(players.clone(), short_deck).deal().err(),
+            Some(PokerError::EmptyDeck)
+        );
+
+        let dealt = new_hand(players, simple_deck()).deal().unwrap();
+        assert_eq!(
+            dealt.play(PlayerMove::Bet(11)).err(),
+            Some(PokerError::InsufficientChips { have: 10, need: 11 })
+        );
+    }
+
+    #[test]
+    fn game_hands_post_antes_then_blinds() {
+        let config = GameConfig::builder()
+            .small_blind(5)
+            .big_blind(10)
+            .ante(5)
+            .structure(BettingStructure::PotLimit)
+            .build();
+        assert_eq!(config.structure(), BettingStructure::PotLimit);
+        assert_eq!(config.max_players(), 10);
+
+        let players = vec![
+            Player::new("Will", 100),
+            Player::new("Jean", 100),
+            Player::new("Bob", 12),
+        ];
+        let hand = Game::new(players, config).new_hand(simple_deck()).unwrap();
+
+        let chips: Vec<usize> = hand.players.iter().map(|(p, _)| p.chips).collect();
+        assert_eq!(chips, vec![95, 90, 0]);
+        assert_eq!(hand.pot, 5 + 5 + 5 + 5 + 7);
+    }
+
+    #[test]
+    fn hands_need_between_two_and_ten_players() {
+        let players = |count: usize| -> Vec<Player> {
+            (0..count)
+                .map(|i| Player::new(format!("Player {}", i + 1), 10))
+                .collect()
+        };
+        let try_new = |players| Hand::try_new(players, full_deck()).err();
+
+        assert_eq!(
+            try_new(players(0)),
+            Some(PokerError::NotEnoughPlayers { min: 2, got: 0 })
+        );
+        assert_eq!(
+            try_new(players(1)),
+            Some(PokerError::NotEnoughPlayers { min: 2, got: 1 })
+        );
+        assert_eq!(try_new(players(2)), None);
+        assert_eq!(try_new(players(10)), None);
+        assert_eq!(
+            try_new(players(11)),
+            Some(PokerError::TooManyPlayers { max: 10, got: 11 })
+        );
+
+        let mut busted = players(2);
+        busted[1].remove_chips(10).unwrap();
+        assert_eq!(
+            try_new(busted),
+            Some(PokerError::NotEnoughPlayers { min: 2, got: 1 })
+        );
+    }
+
+    #[test]
+    fn hands_need_enough_cards_for_hole_cards_and_a_board() {
+        let players: Vec<Player> = (0..9)
+            .map(|i| Player::new(format!("Player {}", i + 1), 10))
+            .collect();
+        let deck = |size| full_deck().split_at(size).unwrap().0;
+
+        assert_eq!(
+            Hand::try_new(players.clone(), deck(20)).err(),
+            Some(PokerError::DeckTooSmall {
+                needed: 23,
+                have: 20
+            })
+        );
+        assert!(Hand::try_new(players, deck(23)).is_ok());
+    }
+
+    #[test]
+    fn post_antes_conserves_chips_and_short_stacks_go_all_in() {
+        let hand = new_hand(
+            vec![
+                Player::new("Will", 10),
+                Player::new("Jean", 2),
+                Player::new("Bob", 20),
+            ],
+            simple_deck(),
+        );
+
+        let hand = post_antes(hand, 5).unwrap();
+        let chips: Vec<usize> = hand.players.iter().map(|(p, _)| p.chips).collect();
+        assert_eq!(chips, vec![5, 0, 15]);
+        assert_eq!(hand.pot, 12);
+        assert_eq!(hand.pot + chips.iter().sum::<usize>(), 32);
+
+        let dealt = Hand {
+            deck: full_deck(),
+            ..hand
+        }
+        .deal()
+        .unwrap();
+        assert_eq!(post_antes(dealt, 5).err(), Some(PokerError::AlreadyDealt));
+    }
+
+    #[test]
+    fn card_formatters() {
+        let hand = "As Kh".parse::<Deck>().unwrap().0;
+        assert_eq!(UnicodeFormatter.format_hand(&hand), "A♠ K♥");
+        assert_eq!(AsciiFormatter.format_hand(&hand), "[As] [Kh]");
+        assert_eq!(
+            HtmlFormatter.format_hand(&hand),
+            concat!(
+                "<span class=\"card\" style=\"color: black\">A♠</span> ",
+                "<span class=\"card\" style=\"color: red\">K♥</span>"
+            )
+        );
+        assert_eq!(AsciiFormatter.format_hand(&[]), "");
+    }
+
+    #[test]
+    fn streets_follow_each_other_in_order() {
+        let streets: Vec<Deal> = std::iter::successors(Some(Deal::Hole), Deal::next).collect();
+        assert_eq!(
+            streets,
+            vec![Deal::Hole, Deal::Flop, Deal::Turn, Deal::River]
+        );
+        assert!(Deal::Turn < Deal::River);
+    }
+
+    #[test]
+    fn streets_display_with_poker_terms() {
+        let names: Vec<String> = [Deal::Hole, Deal::Flop, Deal::Turn, Deal::River]
+            .iter()
+            .map(|street| street.to_string())
+            .collect();
+        assert_eq!(names, vec!["Pre-Flop", "Flop", "Turn", "River"]);
+    }
+
+    #[test]
+    fn cards_dealt_on_each_street() {
+        assert_eq!(Deal::Hole.community_card_count(), 0);
+        assert_eq!(Deal::Flop.community_card_count(), 3);
+        assert_eq!(Deal::Turn.community_card_count(), 1);
+        assert_eq!(Deal::River.community_card_count(), 1);
+
+        assert_eq!(Deal::Hole.total_cards_dealt_to_deck(6), 12);
+        assert_eq!(Deal::Flop.total_cards_dealt_to_deck(6), 4);
+        assert_eq!(Deal::Turn.total_cards_dealt_to_deck(6), 2);
+        assert_eq!(Deal::River.total_cards_dealt_to_deck(6), 2);
+
+        assert_eq!(Deal::Hole.burn_count(), 0);
+        assert_eq!(Deal::Flop.burn_count(), 1);
+        assert_eq!(Deal::Turn.burn_count(), 1);
+        assert_eq!(Deal::River.burn_count(), 1);
+    }
+
+    #[test]
+    fn current_street_follows_the_board() {
+        let street = |board| showdown_hand(&["Kh Kc"], board).current_street();
+        assert_eq!(street(""), Deal::Hole);
+        assert_eq!(street("2c 7d 9h"), Deal::Flop);
+        assert_eq!(street("2c 7d 9h Js"), Deal::Turn);
+        assert_eq!(street("2c 7d 9h Js Qd"), Deal::River);
+    }
+
+    #[test]
+    fn reversing_puts_the_bottom_card_on_top() {
+        let mut deck: Deck = "Ah Kd Qc".parse().unwrap();
+        let bottom = deck.bottom().cloned();
+        deck.reverse();
+        assert_eq!(deck.deal_card(), bottom);
+        assert_eq!(deck, "Kd Ah".parse().unwrap());
+
+        let mut empty = Deck::new();
+        empty.reverse();
+        assert_eq!(empty.bottom(), None);
+        assert_eq!(empty.deal_card(), None);
+    }
+
+    #[test]
+    fn decks_can_be_extended_with_cards_or_other_decks() {
+        let mut deck = Deck::new();
+        deck.extend(full_deck());
+        assert_eq!(deck.len(), 52);
+        assert_eq!(deck, full_deck());
+
+        let mut deck: Deck = "Ah".parse().unwrap();
+        deck.extend(vec![
+            "Kd".parse::<Deck>().unwrap(),
+            "Qc Js".parse().unwrap(),
+        ]);
+        assert_eq!(deck, "Ah Kd Qc Js".parse().unwrap());
+
+        let collected: Deck = deck.iter().cloned().collect();
+        assert_eq!(collected, deck);
+    }
+
+    #[test]
+    fn cards_can_be_collected_into_a_deck() {
+        let hearts = full_deck()
+            .into_iter()
+            .filter(|c| c.suit() == Suit::Heart)
+            .collect::<Deck>();
+        assert_eq!(hearts.len(), 13);
+        assert!(hearts.iter().all(|c| c.suit() == Suit::Heart));
+    }
+
+    #[test]
+    fn peeking_leaves_the_deck_alone() {
+        let deck: Deck = "Ah Kd Qc".parse().unwrap();
+        assert_eq!(deck.peek_n(2), Some(&deck.0[..2]));
+        assert_eq!(deck.peek_n(0), Some(&[][..]));
+        assert_eq!(deck.peek_n(3).map(|cards| cards.len()), Some(3));
+        assert_eq!(deck.peek_n(4), None);
+        assert_eq!(deck, "Ah Kd Qc".parse().unwrap());
+    }
+
+    #[test]
+    fn dealing_several_cards_takes_them_from_the_top() {
+        let mut deck: Deck = "Ah Kd Qc".parse().unwrap();
+        assert_eq!(deck.deal_n(2), Ok("Ah Kd".parse::<Deck>().unwrap().0));
+        assert_eq!(
+            deck.deal_n(2),
+            Err(PokerError::InsufficientCards { need: 2, have: 1 })
+        );
+        assert_eq!(deck.len(), 1);
+        assert_eq!(deck.deal_n(0), Ok(vec![]));
+    }
+
+    #[test]
+    fn samples_are_distinct_cards_from_the_deck() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let deck = full_deck();
+        let sample = deck.sample_without_replacement(7, &mut rng);
+        assert_eq!(sample.len(), 7);
+        assert_eq!(sample.iter().collect::<HashSet<_>>().len(), 7);
+        assert!(sample.iter().all(|card| deck.0.contains(card)));
+        assert_eq!(deck, full_deck());
+
+        let small: Deck = "Ah Kd".parse().unwrap();
+        assert_eq!(small.sample_without_replacement(5, &mut rng).len(), 2);
+    }
+
+    #[test]
+    fn hand_debug_hides_other_players_cards() {
+        let mut hand = showdown_hand(&["Ah Kd", "7c 7d"], "7h Ks 2c");
+        hand.pot = 150;
+        let debug = format!("{:?}", hand);
+        assert!(debug.contains("pot: 150"));
+        assert!(debug.contains("players: 2"));
+        assert!(debug.contains("street: Flop"));
+        assert!(debug.contains("HoleCards(Card(Heart, Ace), Card(Diamond, King))"));
+        assert!(!debug.contains("Card(Diamond, Seven)"));
+    }
+
+    #[test]
+    fn board_is_the_community_cards() {
+        let hand = showdown_hand(&["Kh Kc"], "Ah Kd Qc");
+        assert_eq!(hand.board(), &hand.community_cards[..]);
+        assert_eq!(hand.board_string(), "Ah Kd Qc");
+
+        let hand = showdown_hand(&["Kh Kc"], "");
+        assert_eq!(hand.board_string(), "");
+    }
+
+    #[test]
+    fn noop_shuffler_leaves_the_deck_in_order() {
+        let mut game = Game::new(vec![Player::new("Will", 10)], GameConfig::default())
+            .with_shuffler(Box::new(NoopShuffler));
+        assert_eq!(game.new_hand(full_deck()).unwrap().deck, full_deck());
+    }
+
+    #[test]
+    fn seeded_shufflers_are_repeatable() {
+        let (mut first, mut second) = (full_deck(), full_deck());
+        SeededShuffler { seed: 42 }.shuffle(&mut first);
+        SeededShuffler { seed: 42 }.shuffle(&mut second);
+        assert_eq!(first, second);
+        assert_ne!(first, full_deck());
+    }
+
+    #[test]
+    fn game_rejects_more_players_than_the_config_allows() {
+        let config = GameConfig::builder().max_players(1).build();
+        let mut game = Game::new(
+            vec![Player::new("Will", 10), Player::new("Jean", 2)],
+            config,
+        );
+
+        assert_eq!(
+            game.new_hand(simple_deck()).err(),
+            Some(PokerError::TooManyPlayers { max: 1, got: 2 })
+        );
+    }
+
+    #[test]
+    fn hole_cards_are_categorised() {
+        let category = |cards| hole(cards).hand_category();
+        assert_eq!(category("Ah Kh"), HoleCardCategory::SuitedBroadway);
+        assert_eq!(category("Ah Kd"), HoleCardCategory::OffSuitBroadway);
+        assert_eq!(category("Jh Th"), HoleCardCategory::SuitedBroadway);
+        assert_eq!(category("9c 9d"), HoleCardCategory::PocketPair);
+        assert_eq!(category("9h 8h"), HoleCardCategory::SuitedConnectors);
+        assert_eq!(category("5c 4d"), HoleCardCategory::OffSuitConnectors);
+        assert_eq!(category("Jh 9h"), HoleCardCategory::SuitedGapper);
+        assert_eq!(category("Js 8d"), HoleCardCategory::OffSuitGapper);
+        assert_eq!(category("Kh 4h"), HoleCardCategory::Suited);
+        assert_eq!(category("7c 2d"), HoleCardCategory::OffSuit);
+    }
+
+    #[test]
+    fn suited_pair_and_connected_predicates() {
+        let suited_connectors =
+            HoleCards(Card(Suit::Heart, Rank::Jack), Card(Suit::Heart, Rank::Ten));
+        assert!(suited_connectors.is_suited());
+        assert!(suited_connectors.is_connected());
+        assert!(!suited_connectors.is_pair());
+
+        let pocket_aces = HoleCards(Card(Suit::Heart, Rank::Ace), Card(Suit::Spade, Rank::Ace));
+        assert!(!pocket_aces.is_suited());
+        assert!(!pocket_aces.is_connected());
+        assert!(pocket_aces.is_pair());
+
+        assert!(
+            HoleCards(Card(Suit::Club, Rank::Ace), Card(Suit::Club, Rank::King)).is_connected()
+        );
+        assert!(HoleCards(Card(Suit::Club, Rank::Ace), Card(Suit::Club, Rank::Two)).is_connected());
+        assert!(
+            !HoleCards(Card(Suit::Club, Rank::Jack), Card(Suit::Club, Rank::Nine)).is_connected()
+        );
+        assert!(Card(Suit::Club, Rank::Two).is_suited_with(&Card(Suit::Club, Rank::Ace)));
+    }
+
+    #[test]
+    fn gap_counts_ranks_between_hole_cards() {
+        let hole = |a: Rank, b: Rank| HoleCards(Card(Suit::Heart, a), Card(Suit::Spade, b));
+
+        assert_eq!(hole(Rank::Queen, Rank::Jack).gap(), 0);
+        assert_eq!(hole(Rank::Jack, Rank::Nine).gap(), 1);
+        assert_eq!(hole(Rank::Nine, Rank::Jack).gap(), 1);
+        assert_eq!(hole(Rank::Ten, Rank::Seven).gap(), 2);
+        assert_eq!(hole(Rank::Eight, Rank::Eight).gap(), 0);
+        assert_eq!(hole(Rank::Ace, Rank::King).gap(), 0);
+        assert_eq!(hole(Rank::Ace, Rank::Two).gap(), 0);
+        assert_eq!(hole(Rank::Ace, Rank::Four).gap(), 2);
+        assert_eq!(hole(Rank::Ace, Rank::Seven).gap(), MAX_GAP);
+        assert_eq!(hole(Rank::King, Rank::Two).gap(), MAX_GAP);
+    }
+
+    fn s(s: &str) -> String {
+        s.to_owned()
+    }
+
+    fn hole(s: &str) -> HoleCards {
+        let deck: Deck = s.parse().unwrap();
+        HoleCards(deck[0].clone(), deck[1].clone())
+    }
+
+    // Players are named "Player 1", "Player 2", ... in seat order, each with
+    // no chips behind and the first one to act
+    fn showdown_hand(holes: &[&str], board: &str) -> Hand {
+        Hand {
+            players: holes
+                .iter()
+                .enumerate()
+                .map(|(i, cards)| {
+                    let player = Player::new(format!("Player {}", i + 1), 0);
+                    let state = match i {
+                        0 => PlayerState::Active(hole(cards)),
+                        _ => PlayerState::Dealt(hole(cards)),
+                    };
+                    (player, state)
+                })
+                .collect(),
+            deck: Deck::new(),
+            pot: 0,
+            config: GameConfig::default(),
+            community_cards: board.parse::<Deck>().unwrap().0,
+            log: GameLog::default(),
+            street_bets: vec![0; holes.len()],
+            chips_committed_this_hand: vec![0; holes.len()],
+            active_player_index: Some(0),
+            discard_pile: vec![],
+            chips_in_play: 0,
+            cards_in_play: 2 * holes.len() + board.split_whitespace().count(),
+        }
     }
 
     fn simple_deck() -> Deck {
@@ -0,0 +1,158 @@
+use super::{Card, Deal, Deck, Rank, Suit};
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::cell::Cell;
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicU8, Ordering};
+
+// How suits and cards are written out by their Display impls
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum DisplayConfig {
+    // "♥" and "(♥, Ace)"
+    #[default]
+    Unicode,
+    // "[h]" and "[Ah]"
+    Ascii,
+    // "h" and "Ah"
+    Compact,
+}
+
+// Per thread, so that one thread switching to ASCII doesn't change what
+// everyone else sees
+#[cfg(feature = "std")]
+thread_local! {
+    static DISPLAY_CONFIG: Cell<DisplayConfig> = Cell::new(DisplayConfig::default());
+}
+
+#[cfg(feature = "std")]
+pub fn configure_display(config: DisplayConfig) {
+    DISPLAY_CONFIG.with(|current| current.set(config));
+}
+
+#[cfg(feature = "std")]
+pub fn display_config() -> DisplayConfig {
+    DISPLAY_CONFIG.with(Cell::get)
+}
+
+// Without std there are no thread locals, so one setting is shared by all
+#[cfg(not(feature = "std"))]
+static DISPLAY_CONFIG: AtomicU8 = AtomicU8::new(DisplayConfig::Unicode as u8);
+
+#[cfg(not(feature = "std"))]
+pub fn configure_display(config: DisplayConfig) {
+    DISPLAY_CONFIG.store(config as u8, Ordering::Relaxed);
+}
+
+#[cfg(not(feature = "std"))]
+pub fn display_config() -> DisplayConfig {
+    match DISPLAY_CONFIG.load(Ordering::Relaxed) {
+        x if x == DisplayConfig::Ascii as u8 => DisplayConfig::Ascii,
+        x if x == DisplayConfig::Compact as u8 => DisplayConfig::Compact,
+        _ => DisplayConfig::Unicode,
+    }
+}
+
+impl core::fmt::Display for Suit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match display_config() {
+            DisplayConfig::Unicode => write!(f, "{}", self.icon()),
+            DisplayConfig::Ascii => write!(f, "[{}]", self.letter()),
+            DisplayConfig::Compact => write!(f, "{}", self.letter()),
+        }
+    }
+}
+
+impl core::fmt::Display for Rank {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Rank::Ace => write!(f, "A"),
+            Rank::Two => write!(f, "2"),
+            Rank::Three => write!(f, "3"),
+            Rank::Four => write!(f, "4"),
+            Rank::Five => write!(f, "5"),
+            Rank::Six => write!(f, "6"),
+            Rank::Seven => write!(f, "7"),
+            Rank::Eight => write!(f, "8"),
+            Rank::Nine => write!(f, "9"),
+            Rank::Ten => write!(f, "T"),
+            Rank::Jack => write!(f, "J"),
+            Rank::Queen => write!(f, "Q"),
+            Rank::King => write!(f, "K"),
+        }
+    }
+}
+
+impl core::fmt::Display for Card {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match display_config() {
+            // Cards spell the rank out in full, rather than using its abbreviation
+            DisplayConfig::Unicode => write!(f, "({}, {:?})", self.0, self.1),
+            DisplayConfig::Ascii => write!(f, "[{}]", self.short_name()),
+            DisplayConfig::Compact => write!(f, "{}", self.short_name()),
+        }
+    }
+}
+
+// Always compact, top card first, so that the output parses back into a Deck
+impl core::fmt::Display for Deck {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let cards: Vec<String> = self.iter().map(Card::short_name).collect();
+        write!(f, "{}", cards.join(" "))
+    }
+}
+
+impl core::fmt::Display for Deal {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Deal::Hole => write!(f, "Pre-Flop"),
+            Deal::Flop => write!(f, "Flop"),
+            Deal::Turn => write!(f, "Turn"),
+            Deal::River => write!(f, "River"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configuring_the_display_changes_how_cards_are_written() {
+        let card: Card = "Ah".parse().unwrap();
+        assert_eq!(display_config(), DisplayConfig::Unicode);
+        assert_eq!(card.to_string(), "(♥, Ace)");
+
+        configure_display(DisplayConfig::Ascii);
+        assert_eq!(card.to_string(), "[Ah]");
+        assert_eq!(Suit::Spade.to_string(), "[s]");
+
+        configure_display(DisplayConfig::Compact);
+        assert_eq!(card.to_string(), "Ah");
+        assert_eq!(Suit::Spade.to_string(), "s");
+        assert_eq!(Rank::Ten.to_string(), "T");
+
+        configure_display(DisplayConfig::Unicode);
+        assert_eq!(Suit::Spade.to_string(), "♠");
+    }
+
+    #[test]
+    fn decks_display_their_cards_in_order() {
+        assert_eq!(Deck::new().to_string(), "");
+
+        let deck: Deck = "Ah Kd Qc 2s".parse().unwrap();
+        assert_eq!(deck.to_string(), "Ah Kd Qc 2s");
+        assert_eq!(deck.to_string().parse::<Deck>(), Ok(deck));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn the_display_config_is_per_thread() {
+        configure_display(DisplayConfig::Compact);
+        let other = std::thread::spawn(display_config).join().unwrap();
+        assert_eq!(other, DisplayConfig::Unicode);
+        configure_display(DisplayConfig::Unicode);
+    }
+}
@@ -0,0 +1,251 @@
+use super::{Card, Deal, GamePhase};
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseSuitError(pub(crate) String);
+
+impl core::fmt::Display for ParseSuitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid suit: {:?}", self.0)
+    }
+}
+
+impl core::error::Error for ParseSuitError {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct InvalidSuit(pub(crate) char);
+
+impl core::fmt::Display for InvalidSuit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid suit {:?}, expected one of h, d, c or s", self.0)
+    }
+}
+
+impl core::error::Error for InvalidSuit {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct InvalidRank(pub(crate) u8);
+
+impl core::fmt::Display for InvalidRank {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid rank value {}, expected 2 to 14", self.0)
+    }
+}
+
+impl core::error::Error for InvalidRank {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseRankError(pub(crate) String);
+
+impl core::fmt::Display for ParseRankError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid rank: {:?}", self.0)
+    }
+}
+
+impl core::error::Error for ParseRankError {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseCardError {
+    InvalidRank(ParseRankError),
+    InvalidSuit(ParseSuitError),
+}
+
+impl core::fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseCardError::InvalidRank(e) => write!(f, "invalid card: {}", e),
+            ParseCardError::InvalidSuit(e) => write!(f, "invalid card: {}", e),
+        }
+    }
+}
+
+impl core::error::Error for ParseCardError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ParseCardError::InvalidRank(e) => Some(e),
+            ParseCardError::InvalidSuit(e) => Some(e),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseDeckError {
+    InvalidCard(ParseCardError),
+    DuplicateCard(Card),
+}
+
+impl core::fmt::Display for ParseDeckError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseDeckError::InvalidCard(e) => write!(f, "invalid deck: {}", e),
+            ParseDeckError::DuplicateCard(card) => {
+                write!(f, "invalid deck: {} appears more than once", card)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseDeckError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ParseDeckError::InvalidCard(e) => Some(e),
+            ParseDeckError::DuplicateCard(_) => None,
+        }
+    }
+}
+
+// Extra variants may be added, so matches outside this crate need a wildcard arm
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum PokerError {
+    NoActivePlayer,
+    AlreadyDealt,
+    EmptyDeck,
+    InsufficientChips { have: usize, need: usize },
+    TooManyPlayers { max: usize, got: usize },
+    PlayerNotFound(String),
+    NotEnoughPlayers { min: usize, got: usize },
+    DeckTooSmall { needed: usize, have: usize },
+    StraddleNotAllowed,
+    InsufficientTimeBank { have: u64, need: u64 },
+    InsufficientCards { need: usize, have: usize },
+    RebuyNotAllowed,
+    NoCardsToShow(String),
+    CannotDeal(Deal),
+    NotInHand(String),
+    CardNotHeld(Card),
+    CardNotInDeck(Card),
+    InvalidCard(ParseCardError),
+    InvalidDeck(ParseDeckError),
+    InvalidHandHistory(ParseError),
+    IllegalTransition(GamePhase),
+}
+
+impl core::fmt::Display for PokerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PokerError::NoActivePlayer => write!(f, "no player is active"),
+            PokerError::AlreadyDealt => write!(f, "the hand has already been dealt"),
+            PokerError::EmptyDeck => write!(f, "not enough cards left in the deck"),
+            PokerError::InsufficientChips { have, need } => {
+                write!(f, "insufficient chips: have {}, need {}", have, need)
+            }
+            PokerError::TooManyPlayers { max, got } => {
+                write!(f, "too many players: at most {} can play, got {}", max, got)
+            }
+            PokerError::PlayerNotFound(name) => write!(f, "no player named {:?}", name),
+            PokerError::NotEnoughPlayers { min, got } => {
+                write!(f, "not enough players: need at least {}, got {}", min, got)
+            }
+            PokerError::DeckTooSmall { needed, have } => {
+                write!(f, "deck too small: need {} cards, have {}", needed, have)
+            }
+            PokerError::InsufficientTimeBank { have, need } => {
+                write!(f, "insufficient time bank: have {}s, need {}s", have, need)
+            }
+            PokerError::StraddleNotAllowed => {
+                write!(f, "straddles can only be posted before the flop is dealt")
+            }
+            PokerError::InsufficientCards { need, have } => {
+                write!(f, "insufficient cards: need {}, have {}", need, have)
+            }
+            PokerError::RebuyNotAllowed => write!(f, "players can't rebuy during a hand"),
+            PokerError::NoCardsToShow(name) => write!(f, "{:?} has no cards to show", name),
+            PokerError::CannotDeal(street) => write!(f, "the {} can't be dealt now", street),
+            PokerError::NotInHand(name) => write!(f, "{:?} isn't holding any cards", name),
+            PokerError::CardNotHeld(card) => write!(f, "{} isn't held by the player", card),
+            PokerError::CardNotInDeck(card) => write!(f, "{} isn't in the deck", card),
+            PokerError::InvalidCard(e) => write!(f, "{}", e),
+            PokerError::InvalidDeck(e) => write!(f, "{}", e),
+            PokerError::InvalidHandHistory(e) => write!(f, "invalid hand history: {}", e),
+            PokerError::IllegalTransition(phase) => {
+                write!(f, "that transition isn't allowed from {:?}", phase)
+            }
+        }
+    }
+}
+
+impl core::error::Error for PokerError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            PokerError::InvalidCard(e) => Some(e),
+            PokerError::InvalidDeck(e) => Some(e),
+            PokerError::InvalidHandHistory(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseCardError> for PokerError {
+    fn from(e: ParseCardError) -> PokerError {
+        PokerError::InvalidCard(e)
+    }
+}
+
+impl From<ParseDeckError> for PokerError {
+    fn from(e: ParseDeckError) -> PokerError {
+        PokerError::InvalidDeck(e)
+    }
+}
+
+impl From<ParseError> for PokerError {
+    fn from(e: ParseError) -> PokerError {
+        PokerError::InvalidHandHistory(e)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ValidationError {
+    MultipleActivePlayers(usize),
+    ChipsNotConserved { expected: usize, got: usize },
+    CardsNotConserved { expected: usize, got: usize },
+    DuplicateCard(Card),
+}
+
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationError::MultipleActivePlayers(count) => {
+                write!(f, "{} players are active at once", count)
+            }
+            ValidationError::ChipsNotConserved { expected, got } => {
+                write!(f, "expected {} chips in play, got {}", expected, got)
+            }
+            ValidationError::CardsNotConserved { expected, got } => {
+                write!(f, "expected {} cards in play, got {}", expected, got)
+            }
+            ValidationError::DuplicateCard(card) => write!(f, "{} is in play twice", card),
+        }
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError {
+    line: usize,
+    message: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line,
+            message: message.into(),
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl core::error::Error for ParseError {}
@@ -0,0 +1,508 @@
+use super::{full_deck, Card, Rank};
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pair(pub Card, pub Card);
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThreeOfAKind(pub Card, pub Card, pub Card);
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandValues {
+    HighCard(Card),
+    Pair(Pair),
+    TwoPairs(Pair, Pair),
+    ThreeOfAKind(ThreeOfAKind),
+    Straight((Card, Card, Card, Card, Card)),
+    Flush((Card, Card, Card, Card, Card)),
+    FullHouse { trips: ThreeOfAKind, pair: Pair },
+    FourOfAKind((Card, Card, Card, Card)),
+    StraightFlush((Card, Card, Card, Card, Card)),
+}
+
+impl HandValues {
+    // The cards that make up the hand, so one for a high card and two for a
+    // pair, but no kickers
+    pub fn cards(&self) -> Vec<&Card> {
+        match self {
+            HandValues::HighCard(a) => vec![a],
+            HandValues::Pair(Pair(a, b)) => vec![a, b],
+            HandValues::TwoPairs(Pair(a, b), Pair(c, d)) => vec![a, b, c, d],
+            HandValues::ThreeOfAKind(ThreeOfAKind(a, b, c)) => vec![a, b, c],
+            HandValues::FullHouse {
+                trips: ThreeOfAKind(a, b, c),
+                pair: Pair(d, e),
+            } => vec![a, b, c, d, e],
+            HandValues::FourOfAKind((a, b, c, d)) => vec![a, b, c, d],
+            HandValues::Straight((a, b, c, d, e))
+            | HandValues::Flush((a, b, c, d, e))
+            | HandValues::StraightFlush((a, b, c, d, e)) => vec![a, b, c, d, e],
+        }
+    }
+
+    // 1 for a high card up to 9 for a straight flush, ignoring the ranks
+    // involved. Use hand_score when ties need breaking.
+    pub fn rank_score(&self) -> u32 {
+        match self {
+            HandValues::HighCard(_) => 1,
+            HandValues::Pair(_) => 2,
+            HandValues::TwoPairs(_, _) => 3,
+            HandValues::ThreeOfAKind(_) => 4,
+            HandValues::Straight(_) => 5,
+            HandValues::Flush(_) => 6,
+            HandValues::FullHouse { .. } => 7,
+            HandValues::FourOfAKind(_) => 8,
+            HandValues::StraightFlush(_) => 9,
+        }
+    }
+}
+
+// Classifies the best five card hand that can be made from `cards`, which
+// would usually be two hole cards plus up to five community cards. Panics
+// unless there are between one and seven cards.
+pub fn evaluate_hand(cards: &[Card]) -> HandValues {
+    assert!(
+        (1..=7).contains(&cards.len()),
+        "evaluate_hand needs 1 to 7 cards, got {}",
+        cards.len()
+    );
+    let best = best_five_from_seven(cards);
+    let groups = group_by_rank(&best);
+    let card = |group: usize, i: usize| groups[group][i].clone();
+    let ordered = || {
+        let mut ordered: Vec<Card> = groups.iter().flatten().map(|&card| card.clone()).collect();
+        if straight_high_card(&best) == Some(5) {
+            // The wheel plays its ace low
+            ordered.rotate_left(1);
+        }
+        (
+            ordered[0].clone(),
+            ordered[1].clone(),
+            ordered[2].clone(),
+            ordered[3].clone(),
+            ordered[4].clone(),
+        )
+    };
+
+    match category(&best, &groups) {
+        9 => HandValues::StraightFlush(ordered()),
+        8 => HandValues::FourOfAKind((card(0, 0), card(0, 1), card(0, 2), card(0, 3))),
+        7 => HandValues::FullHouse {
+            trips: ThreeOfAKind(card(0, 0), card(0, 1), card(0, 2)),
+            pair: Pair(card(1, 0), card(1, 1)),
+        },
+        6 => HandValues::Flush(ordered()),
+        5 => HandValues::Straight(ordered()),
+        4 => HandValues::ThreeOfAKind(ThreeOfAKind(card(0, 0), card(0, 1), card(0, 2))),
+        3 => HandValues::TwoPairs(Pair(card(0, 0), card(0, 1)), Pair(card(1, 0), card(1, 1))),
+        2 => HandValues::Pair(Pair(card(0, 0), card(0, 1))),
+        _ => HandValues::HighCard(card(0, 0)),
+    }
+}
+
+// A comparable strength for the best hand in `cards`: higher scores win and
+// equal scores split the pot. Kickers are taken into account.
+pub fn hand_score(cards: &[Card]) -> u32 {
+    score_five(&best_five_from_seven(cards))
+}
+
+pub fn best_five_from_seven(cards: &[Card]) -> Vec<Card> {
+    if cards.len() <= 5 {
+        return cards.to_vec();
+    }
+
+    let mut best: Option<(u32, Vec<Card>)> = None;
+    for combination in combinations(cards.len(), 5) {
+        let five: Vec<Card> = combination.iter().map(|&i| cards[i].clone()).collect();
+        let score = score_five(&five);
+        let better = match &best {
+            Some((best_score, _)) => score > *best_score,
+            None => true,
+        };
+        if better {
+            best = Some((score, five));
+        }
+    }
+
+    best.map(|(_, five)| five).unwrap_or_default()
+}
+
+pub fn is_flush(cards: &[Card]) -> bool {
+    cards.len() == 5 && cards.iter().all(|card| card.is_suited_with(&cards[0]))
+}
+
+pub fn is_straight(cards: &[Card]) -> bool {
+    straight_high_card(cards).is_some()
+}
+
+// An ace high straight flush somewhere in `cards`
+pub fn is_royal_flush(cards: &[Card]) -> bool {
+    if cards.len() < 5 {
+        return false;
+    }
+    match evaluate_hand(cards) {
+        HandValues::StraightFlush((high, _, _, _, _)) => high.rank() == Rank::Ace,
+        _ => false,
+    }
+}
+
+// Groups cards of the same rank together, largest groups first and higher
+// ranks first within groups of the same size.
+pub fn group_by_rank(cards: &[Card]) -> Vec<Vec<&Card>> {
+    let mut groups: Vec<Vec<&Card>> = vec![];
+    for card in cards {
+        match groups
+            .iter_mut()
+            .find(|group| group[0].rank() == card.rank())
+        {
+            Some(group) => group.push(card),
+            None => groups.push(vec![card]),
+        }
+    }
+
+    groups.sort_by(|a, b| {
+        b.len().cmp(&a.len()).then(
+            b[0].rank()
+                .numeric_value()
+                .cmp(&a[0].rank().numeric_value()),
+        )
+    });
+    groups
+}
+
+fn straight_high_card(cards: &[Card]) -> Option<u8> {
+    if cards.len() != 5 {
+        return None;
+    }
+
+    let mut values: Vec<u8> = cards
+        .iter()
+        .map(|card| card.rank().numeric_value())
+        .collect();
+    values.sort_unstable();
+    values.dedup();
+    if values == [2, 3, 4, 5, 14] {
+        return Some(5);
+    }
+
+    match values.len() == 5 && values[4] - values[0] == 4 {
+        true => Some(values[4]),
+        false => None,
+    }
+}
+
+fn category(cards: &[Card], groups: &[Vec<&Card>]) -> u32 {
+    let sizes: Vec<usize> = groups.iter().map(|group| group.len()).collect();
+    match (is_straight(cards), is_flush(cards), sizes.as_slice()) {
+        (true, true, _) => 9,
+        (_, _, [4, ..]) => 8,
+        (_, _, [3, 2]) => 7,
+        (_, true, _) => 6,
+        (true, _, _) => 5,
+        (_, _, [3, ..]) => 4,
+        (_, _, [2, 2, ..]) => 3,
+        (_, _, [2, ..]) => 2,
+        _ => 1,
+    }
+}
+
+// Packs the category into the high bits followed by up to five tie breaking
+// rank values, four bits each, in order of significance.
+fn score_five(cards: &[Card]) -> u32 {
+    let groups = group_by_rank(cards);
+    let mut score = category(cards, &groups);
+    match straight_high_card(cards) {
+        Some(high) => score = (score << 20) | (u32::from(high) << 16),
+        None => {
+            for i in 0..5 {
+                let value = groups
+                    .get(i)
+                    .map_or(0, |group| group[0].rank().numeric_value());
+                score = (score << 4) | u32::from(value);
+            }
+        }
+    }
+    score
+}
+
+pub(crate) fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut result = vec![];
+    let mut indices: Vec<usize> = (0..k).collect();
+    if k > n {
+        return result;
+    }
+
+    loop {
+        result.push(indices.clone());
+        let mut i = k;
+        while i > 0 && indices[i - 1] == n - k + i - 1 {
+            i -= 1;
+        }
+        if i == 0 {
+            return result;
+        }
+        indices[i - 1] += 1;
+        for j in i..k {
+            indices[j] = indices[j - 1] + 1;
+        }
+    }
+}
+
+// Every one of the C(52, 5) = 2,598,960 five card hands, each in deck order.
+// There are far too many to hold at once, so they're made as they're needed.
+pub fn generate_all_5card_hands() -> impl Iterator<Item = [Card; 5]> {
+    FiveCardHands {
+        deck: full_deck().into_iter().collect(),
+        next: Some([0, 1, 2, 3, 4]),
+    }
+}
+
+struct FiveCardHands {
+    deck: Vec<Card>,
+    // Where in the deck the next hand's cards are
+    next: Option<[usize; 5]>,
+}
+
+impl Iterator for FiveCardHands {
+    type Item = [Card; 5];
+
+    fn next(&mut self) -> Option<[Card; 5]> {
+        let mut indices = self.next?;
+        let hand = indices.map(|i| self.deck[i].clone());
+
+        // Moves the last card that can move along one, the same as combinations
+        let n = self.deck.len();
+        let mut i = 5;
+        while i > 0 && indices[i - 1] == n - 5 + i - 1 {
+            i -= 1;
+        }
+        self.next = if i == 0 {
+            None
+        } else {
+            indices[i - 1] += 1;
+            for j in i..5 {
+                indices[j] = indices[j - 1] + 1;
+            }
+            Some(indices)
+        };
+        Some(hand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poker::{Deck, Suit};
+
+    #[test]
+    fn evaluate_hand_finds_the_best_five_cards() {
+        let cards = vec![
+            Card(Suit::Heart, Rank::Ace),
+            Card(Suit::Heart, Rank::King),
+            Card(Suit::Spade, Rank::King),
+            Card(Suit::Club, Rank::Seven),
+            Card(Suit::Diamond, Rank::Seven),
+            Card(Suit::Club, Rank::King),
+            Card(Suit::Spade, Rank::Two),
+        ];
+        assert_eq!(
+            evaluate_hand(&cards),
+            HandValues::FullHouse {
+                trips: ThreeOfAKind(
+                    Card(Suit::Heart, Rank::King),
+                    Card(Suit::Spade, Rank::King),
+                    Card(Suit::Club, Rank::King),
+                ),
+                pair: Pair(
+                    Card(Suit::Club, Rank::Seven),
+                    Card(Suit::Diamond, Rank::Seven),
+                ),
+            }
+        );
+
+        let wheel = vec![
+            Card(Suit::Heart, Rank::Ace),
+            Card(Suit::Club, Rank::Two),
+            Card(Suit::Spade, Rank::Three),
+            Card(Suit::Club, Rank::Four),
+            Card(Suit::Diamond, Rank::Five),
+        ];
+        assert_eq!(
+            evaluate_hand(&wheel),
+            HandValues::Straight((
+                Card(Suit::Diamond, Rank::Five),
+                Card(Suit::Club, Rank::Four),
+                Card(Suit::Spade, Rank::Three),
+                Card(Suit::Club, Rank::Two),
+                Card(Suit::Heart, Rank::Ace),
+            ))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "evaluate_hand needs 1 to 7 cards, got 0")]
+    fn evaluating_no_cards_panics_with_the_card_count() {
+        evaluate_hand(&[]);
+    }
+
+    #[test]
+    fn royal_flushes_are_only_ace_high_straight_flushes() {
+        let cards = |s: &str| s.parse::<Deck>().unwrap().0;
+
+        assert!(is_royal_flush(&cards("As Ks Qs Js Ts")));
+        assert!(is_royal_flush(&cards("2d Th Ah Jh 7c Kh Qh")));
+        assert!(!is_royal_flush(&cards("Kh Qh Jh Th 9h")));
+        assert!(!is_royal_flush(&cards("Ah 2h 3h 4h 5h")));
+        assert!(!is_royal_flush(&cards("As Kd Qs Js Ts")));
+        assert!(!is_royal_flush(&cards("As Ks Qs Js")));
+    }
+
+    #[test]
+    fn hand_score_uses_kickers_to_break_ties() {
+        let board = vec![
+            Card(Suit::Heart, Rank::Ace),
+            Card(Suit::Spade, Rank::Ace),
+            Card(Suit::Club, Rank::Nine),
+            Card(Suit::Diamond, Rank::Six),
+            Card(Suit::Club, Rank::Two),
+        ];
+        let with = |a: Card, b: Card| {
+            let mut cards = board.clone();
+            cards.push(a);
+            cards.push(b);
+            hand_score(&cards)
+        };
+
+        let king_kicker = with(
+            Card(Suit::Heart, Rank::King),
+            Card(Suit::Heart, Rank::Three),
+        );
+        let queen_kicker = with(
+            Card(Suit::Spade, Rank::Queen),
+            Card(Suit::Spade, Rank::Three),
+        );
+        let board_plays = with(
+            Card(Suit::Spade, Rank::Four),
+            Card(Suit::Diamond, Rank::Three),
+        );
+        assert!(king_kicker > queen_kicker);
+        assert!(queen_kicker > board_plays);
+        assert_eq!(
+            board_plays,
+            with(Card(Suit::Club, Rank::Four), Card(Suit::Heart, Rank::Three))
+        );
+    }
+
+    #[test]
+    fn five_cards_are_grouped_and_checked_for_flushes_and_straights() {
+        let cards = |s: &str| s.parse::<Deck>().unwrap().0;
+
+        let full_house = cards("7c Kh 7d Ks Kc");
+        let groups: Vec<Vec<String>> = group_by_rank(&full_house)
+            .iter()
+            .map(|group| group.iter().map(|card| card.to_string()).collect())
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                vec!["(♥, King)", "(♠, King)", "(♣, King)"],
+                vec!["(♣, Seven)", "(♦, Seven)"],
+            ]
+        );
+
+        assert!(is_flush(&cards("2h 9h Jh 4h Kh")));
+        assert!(!is_flush(&cards("2h 9h Jh 4h")));
+        assert!(is_straight(&cards("9c Td Jh Qs 8s")));
+        assert!(is_straight(&cards("Ah 2c 3s 4c 5d")));
+        assert!(!is_straight(&cards("Qh Kc As 2c 3d")));
+    }
+
+    #[test]
+    fn hand_values_list_the_cards_in_the_hand() {
+        let value = |s: &str| evaluate_hand(&s.parse::<Deck>().unwrap().0);
+        let hands = [
+            ("Ah Kd 9c 7s 2h", 1),
+            ("Ah Ad 9c 7s 2h", 2),
+            ("Ah Ad 9c 9s 2h", 4),
+            ("Ah Ad Ac 7s 2h", 3),
+            ("9h Td Jc Qs Kh", 5),
+            ("Ah Kh 9h 7h 2h", 5),
+            ("Ah Ad Ac 2s 2h", 5),
+            ("Ah Ad Ac As 2h", 4),
+            ("9h Th Jh Qh Kh", 5),
+        ];
+        for (cards, len) in hands.iter() {
+            let value = value(cards);
+            let held = value.cards();
+            assert_eq!(held.len(), *len, "{:?}", value);
+            for (i, card) in held.iter().enumerate() {
+                assert!(!held[i + 1..].contains(card), "{:?}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn hand_values_score_by_category() {
+        let score = |s: &str| evaluate_hand(&s.parse::<Deck>().unwrap().0).rank_score();
+        let scores: Vec<u32> = [
+            "Ah Kd 9c 7s 2h",
+            "Ah Ad 9c 7s 2h",
+            "Ah Ad 9c 9s 2h",
+            "Ah Ad Ac 7s 2h",
+            "9h Td Jc Qs Kh",
+            "Ah Kh 9h 7h 2h",
+            "Ah Ad Ac 2s 2h",
+            "Ah Ad Ac As 2h",
+            "9h Th Jh Qh Kh",
+        ]
+        .iter()
+        .map(|cards| score(cards))
+        .collect();
+        assert_eq!(scores, (1..=9).collect::<Vec<u32>>());
+
+        let straight_flush = score("2d 3d 4d 5d Ad");
+        assert!(straight_flush > score("2d 9d 4d 5d Ad"));
+        assert!(score("2d 9d 4d 5d Ad") > score("2d 3c 4d 5d Ad"));
+    }
+
+    #[test]
+    fn every_five_card_hand_is_generated_once() {
+        let deck = full_deck();
+        let mut hands = generate_all_5card_hands();
+        assert_eq!(hands.next(), Some([0, 1, 2, 3, 4].map(|i| deck[i].clone())));
+        assert_eq!(hands.next(), Some([0, 1, 2, 3, 5].map(|i| deck[i].clone())));
+        assert_eq!(
+            generate_all_5card_hands().last(),
+            Some([47, 48, 49, 50, 51].map(|i| deck[i].clone()))
+        );
+        assert_eq!(generate_all_5card_hands().count(), 2_598_960);
+    }
+
+    // Every five card hand there is, checked against the number of each kind
+    // of hand a deck is known to hold
+    #[test]
+    fn validate_hand_evaluation_completeness() {
+        let mut counts = [0; 9];
+        let mut royal_flushes = 0;
+        for hand in generate_all_5card_hands() {
+            let value = evaluate_hand(&hand);
+            assert!(value.cards().iter().all(|card| hand.contains(card)));
+            counts[value.rank_score() as usize - 1] += 1;
+            if is_royal_flush(&hand) {
+                assert!(matches!(value, HandValues::StraightFlush(_)));
+                royal_flushes += 1;
+            }
+        }
+
+        // 4 of the 40 straight flushes are royal
+        assert_eq!(royal_flushes, 4);
+        assert_eq!(
+            counts,
+            [1_302_540, 1_098_240, 123_552, 54_912, 10_200, 5_108, 3_744, 624, 40]
+        );
+    }
+}
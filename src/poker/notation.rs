@@ -0,0 +1,1229 @@
+// A plain text format for a single hand, loosely modelled on chess PGN.
+// Tags describe the game and the deck, then every line is one thing that
+// happened, in the order it happened:
+//
+//   notation ::= tag* seat* event*
+//   tag      ::= "[" name " \"" value "\"]" newline
+//   seat     ::= "Seat " number ": " player " (" chips time-bank? ")" sitting-out? newline
+//   time-bank::= ", " number "s time bank"
+//   sitting-out ::= " sitting out"
+//   event    ::= (post | dealt | action | board | show | muck | collect) newline
+//   post     ::= player ": posts " ("ante ")? number
+//   dealt    ::= "Dealt to " player ": " card " " card
+//   action   ::= player ": " ("folds" | "checks" | "bets " number | "calls " number
+//                 | "raises " number | "discards " cards)
+//   board    ::= ("FLOP" | "TURN" | "RIVER") ": " cards
+//   show     ::= player ": shows " card " " card
+//   muck     ::= player ": mucks"
+//   collect  ::= player ": collects " number
+//   cards    ::= card (" " card)*
+//
+// Cards are written the way Deck parses them, e.g. "Ah". The tags are Event,
+// Date, Hand, SmallBlind, BigBlind, Ante, Structure, MaxPlayers and Deck,
+// which holds the deck the hand was dealt from. A hand doesn't know when it
+// was played, so its Date is always PGN's unknown date "????.??.??".
+//
+// PokerStars hand histories and betting actions are read further down.
+
+use super::{
+    new_hand, ActionRecord, BettingStructure, Card, Deal, Deck, GameConfig, Hand, HoleCards,
+    ParseError, Player, PlayerMove, PlayerPosition, PlayerState, PokerError, MAX_TIME_BANK_SECONDS,
+};
+#[cfg(not(feature = "std"))]
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+impl Hand {
+    pub fn to_pgn_like_notation(&self) -> String {
+        let mut lines = vec![];
+        let tag = |name: &str, value: String| format!("[{} \"{}\"]", name, value);
+        let structure = match self.config.structure {
+            BettingStructure::NoLimit => "NoLimit",
+            BettingStructure::PotLimit => "PotLimit",
+            BettingStructure::FixedLimit => "FixedLimit",
+        };
+        let deck = if self.is_dealt() {
+            &self.log.deck
+        } else {
+            &self.deck
+        };
+        lines.push(tag("Event", "Cash Game".into()));
+        lines.push(tag("Date", "????.??.??".into()));
+        lines.push(tag("Hand", self.log.hand_number.to_string()));
+        lines.push(tag("SmallBlind", self.config.small_blind.to_string()));
+        lines.push(tag("BigBlind", self.config.big_blind.to_string()));
+        lines.push(tag("Ante", self.config.ante.to_string()));
+        lines.push(tag("Structure", structure.into()));
+        lines.push(tag("MaxPlayers", self.config.max_players.to_string()));
+        lines.push(tag("Deck", deck.to_string()));
+
+        // Whatever a player has put in that isn't logged as an action was
+        // posted as an ante or a blind
+        let forced: Vec<usize> = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(seat, (player, _))| {
+                let bet: usize = self
+                    .log
+                    .actions
+                    .iter()
+                    .filter(|record| record.player == player.name)
+                    .map(|record| match record.action {
+                        PlayerMove::Bet(amount)
+                        | PlayerMove::Call(amount)
+                        | PlayerMove::Raise(amount) => amount,
+                        _ => 0,
+                    })
+                    .sum();
+                self.chips_committed_this_hand[seat] - bet
+            })
+            .collect();
+
+        for (seat, (player, state)) in self.players.iter().enumerate() {
+            let time_bank = match player.time_bank_seconds {
+                0 => String::new(),
+                seconds => format!(", {}s time bank", seconds),
+            };
+            let sitting_out = match state {
+                PlayerState::SittingOut => " sitting out",
+                _ => "",
+            };
+            // Until the deal there's nothing logged, but nothing won either
+            let starting_stack = self
+                .log
+                .starting_stacks
+                .iter()
+                .find(|(name, _)| *name == player.name)
+                .map_or(
+                    player.chips + self.chips_committed_this_hand[seat],
+                    |(_, chips)| *chips,
+                );
+            lines.push(format!(
+                "Seat {}: {} ({}{}){}",
+                seat + 1,
+                player.name,
+                starting_stack,
+                time_bank,
+                sitting_out
+            ));
+        }
+
+        let antes: Vec<usize> = forced
+            .iter()
+            .map(|&posted| core::cmp::min(posted, self.config.ante))
+            .collect();
+        for (seat, ante) in antes.iter().enumerate() {
+            if *ante > 0 {
+                lines.push(format!(
+                    "{}: posts ante {}",
+                    self.players[seat].0.name, ante
+                ));
+            }
+        }
+        for (seat, posted) in forced.iter().enumerate() {
+            if *posted > antes[seat] {
+                let blind = posted - antes[seat];
+                lines.push(format!("{}: posts {}", self.players[seat].0.name, blind));
+            }
+        }
+
+        if self.is_dealt() {
+            let mut cards = deck.iter();
+            for (player, state) in self.players.iter() {
+                if *state == PlayerState::SittingOut {
+                    continue;
+                }
+                if let (Some(first), Some(second)) = (cards.next(), cards.next()) {
+                    lines.push(format!(
+                        "Dealt to {}: {} {}",
+                        player.name,
+                        first.short_name(),
+                        second.short_name()
+                    ));
+                }
+            }
+        }
+
+        let mut street = Some(Deal::Hole);
+        while let Some(current) = street {
+            if current > self.current_street() {
+                break;
+            }
+            if current != Deal::Hole {
+                let dealt = current.community_card_count();
+                let end = match current {
+                    Deal::Flop => 3,
+                    Deal::Turn => 4,
+                    _ => 5,
+                };
+                let name = format!("{}", current).to_uppercase();
+                lines.push(format!(
+                    "{}: {}",
+                    name,
+                    short_names(&self.community_cards[end - dealt..end])
+                ));
+            }
+            for record in self.log.actions.iter().filter(|r| r.street == current) {
+                let action = match &record.action {
+                    PlayerMove::Fold => "folds".into(),
+                    PlayerMove::Check => "checks".into(),
+                    PlayerMove::Bet(amount) => format!("bets {}", amount),
+                    PlayerMove::Call(amount) => format!("calls {}", amount),
+                    PlayerMove::Raise(amount) => format!("raises {}", amount),
+                    PlayerMove::Discard(cards) => format!("discards {}", short_names(cards)),
+                };
+                lines.push(format!("{}: {}", record.player, action));
+            }
+            street = current.next();
+        }
+
+        for (player, state) in self.players.iter() {
+            if let PlayerState::Tabled(hole) = state {
+                lines.push(format!(
+                    "{}: shows {} {}",
+                    player.name,
+                    hole.0.short_name(),
+                    hole.1.short_name()
+                ));
+            }
+        }
+        for (player, state) in self.players.iter() {
+            if *state == PlayerState::Mucked {
+                lines.push(format!("{}: mucks", player.name));
+            }
+        }
+        for (name, amount) in self.log.winners.iter() {
+            lines.push(format!("{}: collects {}", name, amount));
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
+
+fn short_names(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(Card::short_name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Reads back what to_pgn_like_notation writes, one production of the grammar
+// at a time, by replaying every event on a fresh hand. The hand has to play
+// out the same way for the notation to be accepted, so cards dealt, boards
+// and pots collected are all checked against what the replay produces.
+pub fn parse_hand_notation(s: &str) -> Result<Hand, ParseError> {
+    let mut lines = s
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Line {
+            n: i + 1,
+            rest: line.trim_end(),
+        })
+        .filter(|line| !line.rest.is_empty())
+        .peekable();
+
+    let mut tags = Tags::default();
+    while let Some(line) = lines.peek_mut() {
+        if !line.rest.starts_with('[') {
+            break;
+        }
+        tag(line, &mut tags)?;
+        lines.next();
+    }
+
+    let mut players = vec![];
+    let mut sitting_out = vec![];
+    while let Some(line) = lines.peek_mut() {
+        if !line.rest.starts_with("Seat ") {
+            break;
+        }
+        let (player, out) = seat(line, players.len() + 1)?;
+        if out {
+            sitting_out.push((line.n, player.name.clone()));
+        }
+        players.push(player);
+        lines.next();
+    }
+
+    let deck = tags
+        .deck
+        .ok_or_else(|| ParseError::new(1, "missing Deck tag"))?;
+    let mut hand = new_hand(players, deck);
+    hand.config = tags.config;
+    hand.log.hand_number = tags.hand_number;
+    for (n, name) in sitting_out {
+        hand = hand
+            .sit_out(&name)
+            .map_err(|e| ParseError::new(n, e.to_string()))?;
+    }
+
+    let mut collected = 0;
+    for mut line in lines {
+        hand = event(&mut line, hand, &mut collected)?;
+    }
+    Ok(hand)
+}
+
+// What's left of a line still to be parsed
+struct Line<'a> {
+    n: usize,
+    rest: &'a str,
+}
+
+impl<'a> Line<'a> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.n, message)
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), ParseError> {
+        self.rest = self
+            .rest
+            .strip_prefix(literal)
+            .ok_or_else(|| self.error(format!("expected \"{}\"", literal)))?;
+        Ok(())
+    }
+
+    fn accept(&mut self, literal: &str) -> bool {
+        match self.rest.strip_prefix(literal) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Everything up to the next `delimiter`, which is consumed too
+    fn until(&mut self, delimiter: &str) -> Result<&'a str, ParseError> {
+        let end = self
+            .rest
+            .find(delimiter)
+            .ok_or_else(|| self.error(format!("expected \"{}\"", delimiter)))?;
+        let taken = &self.rest[..end];
+        self.rest = &self.rest[end + delimiter.len()..];
+        Ok(taken)
+    }
+
+    // Everything up to the last `delimiter`, for player names. Names can
+    // hold anything, but what comes after them on a line never holds the
+    // delimiter.
+    fn until_last(&mut self, delimiter: &str) -> Result<&'a str, ParseError> {
+        let end = self
+            .rest
+            .rfind(delimiter)
+            .ok_or_else(|| self.error(format!("expected \"{}\"", delimiter)))?;
+        let taken = &self.rest[..end];
+        self.rest = &self.rest[end + delimiter.len()..];
+        Ok(taken)
+    }
+
+    fn number<T: core::str::FromStr>(&mut self) -> Result<T, ParseError> {
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let number = self.rest[..end]
+            .parse()
+            .map_err(|_| self.error("expected a number"))?;
+        self.rest = &self.rest[end..];
+        Ok(number)
+    }
+
+    fn cards(&mut self) -> Result<Vec<Card>, ParseError> {
+        let deck: Deck = self
+            .rest
+            .parse()
+            .map_err(|e| self.error(format!("{}", e)))?;
+        self.rest = "";
+        Ok(deck.into_iter().collect())
+    }
+
+    fn end(&self) -> Result<(), ParseError> {
+        match self.rest {
+            "" => Ok(()),
+            rest => Err(self.error(format!("unexpected \"{}\"", rest))),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Tags {
+    config: GameConfig,
+    hand_number: usize,
+    deck: Option<Deck>,
+}
+
+// Unknown tags are skipped, as PGN readers do
+fn tag(line: &mut Line, tags: &mut Tags) -> Result<(), ParseError> {
+    line.expect("[")?;
+    let name = line.until(" \"")?;
+    let value = line.until("\"]")?;
+    line.end()?;
+
+    let mut value = Line {
+        n: line.n,
+        rest: value,
+    };
+    let config = &mut tags.config;
+    match name {
+        "Hand" => tags.hand_number = value.number()?,
+        "SmallBlind" => config.small_blind = value.number()?,
+        "BigBlind" => config.big_blind = value.number()?,
+        "Ante" => config.ante = value.number()?,
+        "MaxPlayers" => config.max_players = value.number()?,
+        "Structure" => {
+            config.structure = match value.rest {
+                "NoLimit" => BettingStructure::NoLimit,
+                "PotLimit" => BettingStructure::PotLimit,
+                "FixedLimit" => BettingStructure::FixedLimit,
+                _ => return Err(value.error("unknown betting structure")),
+            };
+            value.rest = "";
+        }
+        "Deck" => {
+            tags.deck = Some(if value.rest.is_empty() {
+                Deck::new()
+            } else {
+                value.cards()?.into_iter().collect()
+            })
+        }
+        _ => value.rest = "",
+    }
+    value.end()
+}
+
+// Seats are numbered from 1 in order, and say whether the player sat out
+fn seat(line: &mut Line, number: usize) -> Result<(Player, bool), ParseError> {
+    line.expect("Seat ")?;
+    if line.number::<usize>()? != number {
+        return Err(line.error(format!("expected seat {}", number)));
+    }
+    line.expect(": ")?;
+    let name = line.until_last(" (")?;
+    let mut player = Player::new(name, line.number()?);
+    if line.accept(", ") {
+        player.time_bank_seconds = line.number()?;
+        if player.time_bank_seconds > MAX_TIME_BANK_SECONDS {
+            return Err(line.error(format!(
+                "time banks hold at most {}s",
+                MAX_TIME_BANK_SECONDS
+            )));
+        }
+        line.expect("s time bank")?;
+    }
+    line.expect(")")?;
+    let sitting_out = line.accept(" sitting out");
+    line.end()?;
+    Ok((player, sitting_out))
+}
+
+fn event(line: &mut Line, hand: Hand, collected: &mut usize) -> Result<Hand, ParseError> {
+    if line.accept("Dealt to ") {
+        return dealt(line, hand);
+    }
+    for (name, street) in [
+        ("FLOP: ", Deal::Flop),
+        ("TURN: ", Deal::Turn),
+        ("RIVER: ", Deal::River),
+    ] {
+        if line.accept(name) {
+            return board(line, hand, street);
+        }
+    }
+
+    let name = line.until_last(": ")?;
+    let seat = hand.seat_of(name).map_err(|e| line.error(e.to_string()))?;
+
+    let mut hand = hand;
+    if line.accept("posts ") {
+        if hand.is_dealt() {
+            return Err(line.error("forced bets are posted before the deal"));
+        }
+        let ante = line.accept("ante ");
+        let amount = line.number()?;
+        if ante {
+            hand.post_forced_bet(seat, amount);
+        } else {
+            hand.post_blind(seat, amount);
+        }
+    } else if line.accept("shows ") {
+        let cards = line.cards()?;
+        hand.force_show_cards(name)
+            .map_err(|e| line.error(e.to_string()))?;
+        if !holds(&hand, seat, &cards) {
+            return Err(line.error(format!("{} wasn't dealt those cards", name)));
+        }
+    } else if line.accept("mucks") {
+        // Just as at showdown, the cards go into the discard pile unseen
+        let (_, state) = &mut hand.players[seat];
+        let hole = state
+            .hole_cards()
+            .cloned()
+            .ok_or_else(|| line.error(format!("{} has no cards to muck", name)))?;
+        hand.discard_pile.extend(vec![hole.0, hole.1]);
+        *state = PlayerState::Mucked;
+    } else if line.accept("collects ") {
+        let amount = line.number()?;
+        if *collected == 0 {
+            hand = settle(hand).map_err(|e| line.error(e.to_string()))?;
+        }
+        if hand.log.winners.get(*collected) != Some(&(name.to_owned(), amount)) {
+            return Err(line.error(format!("{} doesn't win {}", name, amount)));
+        }
+        *collected += 1;
+    } else {
+        let mv = action(line)?;
+        if hand.active_player_index != Some(seat) {
+            return Err(line.error(format!("it isn't {}'s turn", name)));
+        }
+        hand = hand.play(mv).map_err(|e| line.error(e.to_string()))?;
+    }
+    line.end()?;
+    Ok(hand)
+}
+
+fn action(line: &mut Line) -> Result<PlayerMove, ParseError> {
+    Ok(if line.accept("folds") {
+        PlayerMove::Fold
+    } else if line.accept("checks") {
+        PlayerMove::Check
+    } else if line.accept("bets ") {
+        PlayerMove::Bet(line.number()?)
+    } else if line.accept("calls ") {
+        PlayerMove::Call(line.number()?)
+    } else if line.accept("raises ") {
+        PlayerMove::Raise(line.number()?)
+    } else if line.accept("discards ") {
+        PlayerMove::Discard(line.cards()?)
+    } else {
+        return Err(line.error("expected an action"));
+    })
+}
+
+// The first player dealt to deals everyone in, the rest just have to match
+fn dealt(line: &mut Line, hand: Hand) -> Result<Hand, ParseError> {
+    let name = line.until_last(": ")?;
+    let cards = line.cards()?;
+    let hand = if hand.is_dealt() {
+        hand
+    } else {
+        hand.deal().map_err(|e| line.error(e.to_string()))?
+    };
+    let seat = hand.seat_of(name).map_err(|e| line.error(e.to_string()))?;
+    if !holds(&hand, seat, &cards) {
+        return Err(line.error(format!("{} wasn't dealt those cards", name)));
+    }
+    Ok(hand)
+}
+
+fn holds(hand: &Hand, seat: usize, cards: &[Card]) -> bool {
+    match hand.players[seat].1.hole_cards() {
+        Some(hole) => cards == [hole.0.clone(), hole.1.clone()],
+        None => false,
+    }
+}
+
+fn board(line: &mut Line, hand: Hand, street: Deal) -> Result<Hand, ParseError> {
+    let cards = line.cards()?;
+    let hand = hand
+        .deal_street(street)
+        .map_err(|e| line.error(e.to_string()))?;
+    if hand.community_cards[hand.community_cards.len() - cards.len()..] != cards[..] {
+        return Err(line.error(format!("the {} doesn't match the deck", street)));
+    }
+    Ok(hand)
+}
+
+// A pot collected by the only player left was folded to them, otherwise it
+// went to showdown and is split between the best hands
+fn settle(mut hand: Hand) -> Result<Hand, PokerError> {
+    let mucked = hand
+        .players
+        .iter()
+        .any(|(_, state)| *state == PlayerState::Mucked);
+    match hand.eligible_winners().as_slice() {
+        [winner] if !mucked => {
+            let winner = winner.name.clone();
+            hand.fold_all_but(&winner)
+        }
+        _ => {
+            hand.award_pot();
+            Ok(hand)
+        }
+    }
+}
+
+// PokerStars hand histories
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SeatedPlayer {
+    pub seat: usize,
+    pub player: Player,
+    pub position: Option<PlayerPosition>,
+    pub hole_cards: Option<HoleCards>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CompletedHand {
+    pub id: u64,
+    pub players: Vec<SeatedPlayer>,
+    pub community_cards: Vec<Card>,
+    pub actions: Vec<ActionRecord>,
+    pub winners: Vec<(String, usize)>,
+}
+
+// Reads a PokerStars hold'em hand history. Dollar amounts are converted to
+// cents so that both cash game and tournament chip amounts fit in a usize.
+pub fn parse_pokerstars_hand_history(input: &str) -> Result<CompletedHand, PokerError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    let (n, header) = lines
+        .next()
+        .ok_or_else(|| ParseError::new(1, "empty hand history"))?;
+    let id = header
+        .strip_prefix("PokerStars Hand #")
+        .and_then(|rest| rest.split(':').next())
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| ParseError::new(n, "expected a PokerStars hand header"))?;
+
+    let mut hand = CompletedHand {
+        id,
+        players: vec![],
+        community_cards: vec![],
+        actions: vec![],
+        winners: vec![],
+    };
+    let mut button = None;
+    let mut street = Deal::Hole;
+    let mut pot = 0;
+    let mut invested: Vec<usize> = vec![];
+
+    for (n, line) in lines {
+        if line.starts_with("*** SUMMARY ***") {
+            break;
+        }
+
+        if let Some(rest) = line.strip_prefix("Table ") {
+            button = rest
+                .split("Seat #")
+                .nth(1)
+                .and_then(|seat| seat.split_whitespace().next())
+                .and_then(|seat| seat.parse::<usize>().ok());
+        } else if let Some(rest) = line.strip_prefix("Seat ") {
+            let seated = parse_seat(rest).ok_or_else(|| ParseError::new(n, "invalid seat"))?;
+            hand.players.push(SeatedPlayer {
+                position: match button {
+                    Some(button) if button == seated.seat => Some(PlayerPosition::Button),
+                    _ => None,
+                },
+                ..seated
+            });
+            invested.push(0);
+        } else if let Some(rest) = line.strip_prefix("*** ") {
+            let next = match rest.split(" ***").next() {
+                Some("FLOP") => Deal::Flop,
+                Some("TURN") => Deal::Turn,
+                Some("RIVER") => Deal::River,
+                _ => continue,
+            };
+            let cards = line
+                .rsplit('[')
+                .next()
+                .and_then(|cards| cards.strip_suffix(']'))
+                .ok_or_else(|| ParseError::new(n, "expected the new board cards"))?;
+            let cards: Deck = cards
+                .parse()
+                .map_err(|e| ParseError::new(n, format!("{}", e)))?;
+            hand.community_cards.extend(cards);
+            street = next;
+            invested.iter_mut().for_each(|amount| *amount = 0);
+        } else if let Some(rest) = line.strip_prefix("Dealt to ") {
+            let seat = find_seat(&hand.players, rest)
+                .ok_or_else(|| ParseError::new(n, "cards dealt to an unknown player"))?;
+            let name_len = hand.players[seat].player.name.len();
+            hand.players[seat].hole_cards = Some(
+                parse_hole_cards(&rest[name_len..])
+                    .ok_or_else(|| ParseError::new(n, "invalid hole cards"))?,
+            );
+        } else if let Some(seat) = find_seat(&hand.players, line) {
+            let name = hand.players[seat].player.name.clone();
+            let rest = &line[name.len()..];
+
+            if let Some(amount) = rest.strip_prefix(" collected ") {
+                let amount = amount
+                    .split_whitespace()
+                    .next()
+                    .and_then(parse_amount)
+                    .ok_or_else(|| ParseError::new(n, "invalid amount collected"))?;
+                hand.winners.push((name, amount));
+                continue;
+            }
+
+            let rest = match rest.strip_prefix(": ") {
+                Some(rest) => rest,
+                None => continue,
+            };
+            if let Some(cards) = rest.strip_prefix("shows ") {
+                hand.players[seat].hole_cards = Some(
+                    parse_hole_cards(cards)
+                        .ok_or_else(|| ParseError::new(n, "invalid cards shown"))?,
+                );
+            } else if let Some(post) = rest.strip_prefix("posts ") {
+                let amount = post
+                    .split_whitespace()
+                    .last()
+                    .and_then(parse_amount)
+                    .ok_or_else(|| ParseError::new(n, "invalid amount posted"))?;
+                if post.starts_with("small blind") {
+                    hand.players[seat].position = Some(PlayerPosition::SmallBlind);
+                } else if post.starts_with("big blind") {
+                    hand.players[seat].position = Some(PlayerPosition::BigBlind);
+                }
+                if !post.starts_with("the ante") {
+                    invested[seat] += amount;
+                }
+                pot += amount;
+            } else if let Some(action) = parse_pokerstars_action(rest, invested[seat]) {
+                let action = action.map_err(|message| ParseError::new(n, message))?;
+                let pot_before = pot;
+                if let PlayerMove::Bet(amount)
+                | PlayerMove::Call(amount)
+                | PlayerMove::Raise(amount) = action
+                {
+                    invested[seat] += amount;
+                    pot += amount;
+                }
+                hand.actions.push(ActionRecord {
+                    street,
+                    player: name,
+                    action,
+                    pot_before,
+                    pot_after: pot,
+                });
+            }
+        }
+    }
+
+    if hand.players.is_empty() {
+        return Err(ParseError::new(n, "no players are seated").into());
+    }
+    Ok(hand)
+}
+
+pub struct Notation;
+
+impl Notation {
+    // Reads the betting actions from lines such as "Alice: raises $2 to $3"
+    // or just "calls $10". Blinds count towards what a player has put in but
+    // aren't moves themselves, and "***" street headers start a new street.
+    pub fn from_pokerstars(input: &str) -> Result<Vec<PlayerMove>, PokerError> {
+        let mut invested: Vec<(&str, usize)> = vec![];
+        let mut moves = vec![];
+
+        for (n, line) in input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+        {
+            if line.starts_with("***") {
+                invested.clear();
+                continue;
+            }
+
+            let (name, action) = match line.find(": ") {
+                Some(i) => (&line[..i], &line[i + 2..]),
+                None => ("", line),
+            };
+            let seat = match invested.iter().position(|(player, _)| *player == name) {
+                Some(seat) => seat,
+                None => {
+                    invested.push((name, 0));
+                    invested.len() - 1
+                }
+            };
+
+            if let Some(post) = action.strip_prefix("posts ") {
+                let amount = post
+                    .split_whitespace()
+                    .last()
+                    .and_then(parse_amount)
+                    .ok_or_else(|| ParseError::new(n, "invalid amount posted"))?;
+                if !post.starts_with("the ante") {
+                    invested[seat].1 += amount;
+                }
+                continue;
+            }
+
+            let mv = parse_pokerstars_action(action, invested[seat].1)
+                .ok_or_else(|| ParseError::new(n, format!("not a betting action: {:?}", line)))?
+                .map_err(|message| ParseError::new(n, message))?;
+            if let PlayerMove::Bet(amount) | PlayerMove::Call(amount) | PlayerMove::Raise(amount) =
+                mv
+            {
+                invested[seat].1 += amount;
+            }
+            moves.push(mv);
+        }
+
+        Ok(moves)
+    }
+}
+
+// Parses the action part of a line such as "raises $2 to $3", where
+// `invested` is what the player has already put in on this street. Raises
+// are recorded as the chips added, not the total they are raised to. Returns
+// None for anything that isn't a betting action.
+fn parse_pokerstars_action(action: &str, invested: usize) -> Option<Result<PlayerMove, String>> {
+    let action = action.trim_end_matches(" and is all-in");
+    let mut words = action.split_whitespace();
+    let verb = words.next()?;
+    let amount = |word: Option<&str>| {
+        word.and_then(parse_amount)
+            .ok_or_else(|| format!("invalid amount in {:?}", action))
+    };
+
+    let mv = match verb {
+        "folds" => Ok(PlayerMove::Fold),
+        "checks" => Ok(PlayerMove::Check),
+        "bets" => amount(words.next()).map(PlayerMove::Bet),
+        "calls" => amount(words.next()).map(PlayerMove::Call),
+        "raises" => amount(words.nth(2)).and_then(|to| match to.checked_sub(invested) {
+            Some(added) => Ok(PlayerMove::Raise(added)),
+            None => Err(format!(
+                "raise to {} is less than {} already in",
+                to, invested
+            )),
+        }),
+        _ => return None,
+    };
+    Some(mv)
+}
+
+// "$1.50" is 150 cents, while "1500" is a plain chip count
+fn parse_amount(amount: &str) -> Option<usize> {
+    let amount = amount
+        .trim_matches(|c| c == '(' || c == ')')
+        .replace(',', "");
+    match amount.strip_prefix('$') {
+        Some(dollars) => {
+            let mut parts = dollars.splitn(2, '.');
+            let whole: usize = parts.next()?.parse().ok()?;
+            let cents = match parts.next() {
+                Some(cents) if cents.len() == 2 => cents.parse().ok()?,
+                Some(cents) if cents.len() == 1 => cents.parse::<usize>().ok()? * 10,
+                Some(_) => return None,
+                None => 0,
+            };
+            Some(whole * 100 + cents)
+        }
+        None => amount.parse().ok(),
+    }
+}
+
+// "1: Will ($100 in chips)"
+fn parse_seat(seat: &str) -> Option<SeatedPlayer> {
+    let (number, rest) = seat.split_once(": ")?;
+    let open = rest.rfind(" (")?;
+    let chips = rest[open + 2..]
+        .split_whitespace()
+        .next()
+        .and_then(parse_amount)?;
+    Some(SeatedPlayer {
+        seat: number.parse().ok()?,
+        player: Player {
+            name: rest[..open].to_owned(),
+            chips,
+            time_bank_seconds: 0,
+        },
+        position: None,
+        hole_cards: None,
+    })
+}
+
+// " [Ah Kh] (a flush, Ace high)"
+fn parse_hole_cards(cards: &str) -> Option<HoleCards> {
+    let cards = cards.split('[').nth(1)?.split(']').next()?;
+    let deck: Deck = cards.parse().ok()?;
+    match deck.len() {
+        2 => Some(HoleCards(deck[0].clone(), deck[1].clone())),
+        _ => None,
+    }
+}
+
+// Lines start with the player's name, which may itself contain spaces, so
+// prefer the longest seated name that matches
+fn find_seat(players: &[SeatedPlayer], line: &str) -> Option<usize> {
+    players
+        .iter()
+        .enumerate()
+        .filter(|(_, seated)| {
+            line.starts_with(&seated.player.name)
+                && matches!(
+                    line[seated.player.name.len()..].chars().next(),
+                    Some(':') | Some(' ')
+                )
+        })
+        .max_by_key(|(_, seated)| seated.player.name.len())
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poker::{full_deck, Game, GameConfig, NoopShuffler, Player, StateMachine};
+
+    // Will, Jean and Bob play a hand with 1/2 blinds that goes to showdown
+    fn complete_hand() -> Hand {
+        let config = GameConfig::builder().small_blind(1).big_blind(2).build();
+        let players = vec![
+            Player::new("Will", 100),
+            Player::new("Jean", 50),
+            Player::new("Bob", 80),
+        ];
+        let mut game = Game::new(players, config).with_shuffler(Box::new(NoopShuffler));
+        let mut machine = StateMachine::new(game.new_hand(full_deck()).unwrap());
+        let moves = vec![
+            None,
+            Some(PlayerMove::Raise(6)),
+            Some(PlayerMove::Call(5)),
+            Some(PlayerMove::Fold),
+            None,
+            Some(PlayerMove::Check),
+            Some(PlayerMove::Bet(10)),
+            Some(PlayerMove::Call(10)),
+            None,
+            None,
+            None,
+            None,
+        ];
+        for mv in moves {
+            machine.transition(mv).unwrap();
+        }
+        machine.hand().clone()
+    }
+
+    #[test]
+    fn hands_are_written_one_event_per_line() {
+        let notation = complete_hand().to_pgn_like_notation();
+        let lines: Vec<&str> = notation.lines().collect();
+        assert_eq!(
+            lines[..8],
+            [
+                "[Event \"Cash Game\"]",
+                "[Date \"????.??.??\"]",
+                "[Hand \"1\"]",
+                "[SmallBlind \"1\"]",
+                "[BigBlind \"2\"]",
+                "[Ante \"0\"]",
+                "[Structure \"NoLimit\"]",
+                "[MaxPlayers \"10\"]",
+            ]
+        );
+        assert_eq!(lines[8], format!("[Deck \"{}\"]", full_deck()));
+        assert_eq!(
+            lines[9..],
+            [
+                "Seat 1: Will (100)",
+                "Seat 2: Jean (50)",
+                "Seat 3: Bob (80)",
+                "Jean: posts 1",
+                "Bob: posts 2",
+                "Dealt to Will: Ad 2d",
+                "Dealt to Jean: 3d 4d",
+                "Dealt to Bob: 5d 6d",
+                "Will: raises 6",
+                "Jean: calls 5",
+                "Bob: folds",
+                "FLOP: 8d 9d Td",
+                "Will: checks",
+                "Jean: bets 10",
+                "Will: calls 10",
+                "TURN: Qd",
+                "RIVER: Ah",
+                "Jean: mucks",
+                "Will: collects 34",
+            ]
+        );
+    }
+
+    #[test]
+    fn undealt_hands_list_the_seats_and_deck() {
+        let hand = crate::poker::new_hand(
+            vec![Player::new("Will", 10), Player::new("Jean", 10)],
+            "Ah Kd Qc Js".parse().unwrap(),
+        )
+        .sit_out("Jean")
+        .unwrap();
+        let notation = hand.to_pgn_like_notation();
+        let lines: Vec<&str> = notation.lines().skip(8).collect();
+        assert_eq!(
+            lines,
+            [
+                "[Deck \"Ah Kd Qc Js\"]",
+                "Seat 1: Will (10)",
+                "Seat 2: Jean (10) sitting out",
+            ]
+        );
+    }
+
+    #[test]
+    fn notation_parses_back_into_the_same_hand() {
+        let hand = complete_hand();
+        assert_eq!(parse_hand_notation(&hand.to_pgn_like_notation()), Ok(hand));
+
+        // Names are read up to the last separator, so they can hold them too
+        let players = vec![Player::new("Bob (NL)", 10), Player::new("Jean: JJ", 10)];
+        let hand = crate::poker::new_hand(players, full_deck())
+            .deal()
+            .unwrap()
+            .play(PlayerMove::Bet(2))
+            .unwrap()
+            .play(PlayerMove::Fold)
+            .unwrap()
+            .fold_all_but("Bob (NL)")
+            .unwrap();
+        let notation = hand.to_pgn_like_notation();
+        assert!(notation.contains("Seat 1: Bob (NL) (10)\n"));
+        assert!(notation.contains("Dealt to Jean: JJ: 3d 4d\n"));
+        assert_eq!(parse_hand_notation(&notation), Ok(hand));
+
+        let mut hand = crate::poker::new_hand(
+            vec![Player::new("Will", 10), Player::new("Jean", 10)],
+            "Ah Kd Qc Js".parse().unwrap(),
+        )
+        .sit_out("Jean")
+        .unwrap();
+        hand.players[0].0.time_bank_seconds = 30;
+        assert_eq!(parse_hand_notation(&hand.to_pgn_like_notation()), Ok(hand));
+    }
+
+    #[test]
+    fn seats_show_the_stacks_players_were_dealt_in_with() {
+        let hand = complete_hand();
+        assert_eq!(
+            hand.log.starting_stacks,
+            vec![
+                ("Will".to_string(), 100),
+                ("Jean".to_string(), 50),
+                ("Bob".to_string(), 80),
+            ]
+        );
+
+        // A bounty paid after the blinds have gone in but before the deal
+        let hand = crate::poker::new_hand(
+            vec![Player::new("Will", 10), Player::new("Jean", 10)],
+            full_deck(),
+        );
+        let mut hand = hand.transfer_chips("Jean", "Will", 4).unwrap();
+        hand.post_blind(1, 2);
+        let hand = hand.deal().unwrap().play(PlayerMove::Fold).unwrap();
+        let notation = hand.to_pgn_like_notation();
+        assert!(notation.contains("Seat 1: Will (14)\nSeat 2: Jean (6)\n"));
+        assert_eq!(parse_hand_notation(&notation), Ok(hand));
+    }
+
+    #[test]
+    fn hands_folded_to_one_player_parse_back_with_antes() {
+        let config = GameConfig::builder()
+            .small_blind(1)
+            .big_blind(2)
+            .ante(1)
+            .build();
+        let players = vec![Player::new("Will", 100), Player::new("Jean", 50)];
+        let mut game = Game::new(players, config).with_shuffler(Box::new(NoopShuffler));
+        let mut machine = StateMachine::new(game.new_hand(full_deck()).unwrap());
+        machine.transition(None).unwrap();
+        machine.transition(Some(PlayerMove::Fold)).unwrap();
+        let hand = machine.hand().clone();
+
+        let notation = hand.to_pgn_like_notation();
+        assert!(notation.contains("Will: posts ante 1\nJean: posts ante 1\n"));
+        assert_eq!(parse_hand_notation(&notation), Ok(hand));
+    }
+
+    #[test]
+    fn events_that_dont_replay_are_rejected_with_their_line() {
+        let notation = complete_hand()
+            .to_pgn_like_notation()
+            .replace("Will: raises 6", "Jean: raises 6");
+        let error = parse_hand_notation(&notation).unwrap_err();
+        assert_eq!(error.line(), 18);
+        assert_eq!(error.to_string(), "line 18: it isn't Jean's turn");
+
+        let notation = complete_hand()
+            .to_pgn_like_notation()
+            .replace("FLOP: 8d 9d Td", "FLOP: 8d 9d Jd");
+        assert_eq!(parse_hand_notation(&notation).unwrap_err().line(), 21);
+
+        let notation = complete_hand()
+            .to_pgn_like_notation()
+            .replace("Seat 2: Jean (50)", "Seat 2: Jean (50, 10000s time bank)");
+        assert_eq!(
+            parse_hand_notation(&notation).unwrap_err().to_string(),
+            "line 11: time banks hold at most 120s"
+        );
+    }
+
+    #[test]
+    fn pokerstars_actions_parse_into_moves() {
+        let preflop: String = POKERSTARS_HAND
+            .lines()
+            .skip(5)
+            .take_while(|line| !line.starts_with("*** FLOP"))
+            .filter(|line| !line.starts_with("Dealt to"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        assert_eq!(
+            Notation::from_pokerstars(&preflop),
+            Ok(vec![
+                PlayerMove::Raise(300),
+                PlayerMove::Fold,
+                PlayerMove::Call(200)
+            ])
+        );
+
+        let chips = "Will: posts big blind 200\nJean: raises 400 to 600\nWill: raises 1200 to 1800\nJean: checks";
+        assert_eq!(
+            Notation::from_pokerstars(chips),
+            Ok(vec![
+                PlayerMove::Raise(600),
+                PlayerMove::Raise(1600),
+                PlayerMove::Check
+            ])
+        );
+
+        assert_eq!(
+            Notation::from_pokerstars("calls $10\nshows [Ah Kh]"),
+            Err(PokerError::InvalidHandHistory(ParseError::new(
+                2,
+                "not a betting action: \"shows [Ah Kh]\""
+            )))
+        );
+    }
+
+    #[test]
+    fn pokerstars_hand_histories_parse_into_completed_hands() {
+        let hand = parse_pokerstars_hand_history(POKERSTARS_HAND).unwrap();
+
+        assert_eq!(hand.id, 208_980_123_456);
+        let names: Vec<&str> = hand
+            .players
+            .iter()
+            .map(|seated| seated.player.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Alice", "Bob Smith", "Carol"]);
+        assert_eq!(hand.players[0].player.chips, 10_000);
+        let positions: Vec<Option<PlayerPosition>> =
+            hand.players.iter().map(|seated| seated.position).collect();
+        assert_eq!(
+            positions,
+            vec![
+                Some(PlayerPosition::Button),
+                Some(PlayerPosition::SmallBlind),
+                Some(PlayerPosition::BigBlind)
+            ]
+        );
+        assert_eq!(hand.players[0].hole_cards, Some(hole("Ah Kh")));
+        assert_eq!(hand.players[1].hole_cards, None);
+        assert_eq!(hand.players[2].hole_cards, Some(hole("Tc Ts")));
+        assert_eq!(
+            hand.community_cards,
+            "2h 7h Td Qs 5h".parse::<Deck>().unwrap().0
+        );
+
+        let moves: Vec<(Deal, &str, PlayerMove)> = hand
+            .actions
+            .iter()
+            .map(|record| (record.street, record.player.as_str(), record.action.clone()))
+            .collect();
+        assert_eq!(
+            moves,
+            vec![
+                (Deal::Hole, "Alice", PlayerMove::Raise(300)),
+                (Deal::Hole, "Bob Smith", PlayerMove::Fold),
+                (Deal::Hole, "Carol", PlayerMove::Call(200)),
+                (Deal::Flop, "Carol", PlayerMove::Check),
+                (Deal::Flop, "Alice", PlayerMove::Bet(400)),
+                (Deal::Flop, "Carol", PlayerMove::Call(400)),
+                (Deal::Turn, "Carol", PlayerMove::Check),
+                (Deal::Turn, "Alice", PlayerMove::Check),
+                (Deal::River, "Carol", PlayerMove::Bet(1000)),
+                (Deal::River, "Alice", PlayerMove::Call(1000)),
+            ]
+        );
+        assert_eq!(hand.actions[0].pot_before, 150);
+        assert_eq!(hand.actions.last().unwrap().pot_after, 3450);
+        assert_eq!(hand.winners, vec![("Alice".to_string(), 3400)]);
+    }
+
+    #[test]
+    fn malformed_pokerstars_hand_histories_are_rejected() {
+        assert_eq!(
+            parse_pokerstars_hand_history("Full Tilt Hand #1"),
+            Err(PokerError::InvalidHandHistory(ParseError::new(
+                1,
+                "expected a PokerStars hand header"
+            )))
+        );
+        let bad_board = POKERSTARS_HAND.replace("[2h 7h Td]", "[2h 7h Tx]");
+        assert_eq!(
+            parse_pokerstars_hand_history(&bad_board).map_err(|e| match e {
+                PokerError::InvalidHandHistory(e) => e.line(),
+                _ => 0,
+            }),
+            Err(13)
+        );
+    }
+
+    fn hole(s: &str) -> HoleCards {
+        let deck: Deck = s.parse().unwrap();
+        HoleCards(deck[0].clone(), deck[1].clone())
+    }
+
+    const POKERSTARS_HAND: &str = "\
+PokerStars Hand #208980123456:  Hold'em No Limit ($0.50/$1.00 USD) - 2020/01/01 12:00:00 ET
+Table 'Alpha II' 6-max Seat #1 is the button
+Seat 1: Alice ($100 in chips)
+Seat 2: Bob Smith ($85.50 in chips)
+Seat 3: Carol ($100 in chips)
+Bob Smith: posts small blind $0.50
+Carol: posts big blind $1
+*** HOLE CARDS ***
+Dealt to Alice [Ah Kh]
+Alice: raises $2 to $3
+Bob Smith: folds
+Carol: calls $2
+*** FLOP *** [2h 7h Td]
+Carol: checks
+Alice: bets $4
+Carol: calls $4
+*** TURN *** [2h 7h Td] [Qs]
+Carol: checks
+Alice: checks
+*** RIVER *** [2h 7h Td Qs] [5h]
+Carol: bets $10
+Alice: calls $10
+*** SHOW DOWN ***
+Carol: shows [Tc Ts] (three of a kind, Tens)
+Alice: shows [Ah Kh] (a flush, Ace high)
+Alice collected $34 from pot
+*** SUMMARY ***
+Total pot $34.50 | Rake $0.50
+Board [2h 7h Td Qs 5h]
+Seat 1: Alice (button) showed [Ah Kh] and won ($34) with a flush, Ace high
+Seat 2: Bob Smith (small blind) folded before Flop
+Seat 3: Carol (big blind) showed [Tc Ts] and lost with three of a kind, Tens
+";
+}
@@ -0,0 +1,412 @@
+use super::hand_eval::{combinations, hand_score};
+use super::{full_deck, Card, Deck, HoleCards, PlayerPosition, Rank, Suit};
+use rand::seq::SliceRandom;
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+// Simulation
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SimulationResult {
+    pub iterations: u32,
+    pub wins: Vec<u32>,
+    pub splits: Vec<u32>,
+    pub losses: Vec<u32>,
+}
+
+impl SimulationResult {
+    pub fn win_rate(&self, player_index: usize) -> f64 {
+        self.rate(&self.wins, player_index)
+    }
+
+    pub fn split_rate(&self, player_index: usize) -> f64 {
+        self.rate(&self.splits, player_index)
+    }
+
+    pub fn loss_rate(&self, player_index: usize) -> f64 {
+        self.rate(&self.losses, player_index)
+    }
+
+    fn rate(&self, counts: &[u32], player_index: usize) -> f64 {
+        match self.iterations {
+            0 => 0.0,
+            iterations => f64::from(counts[player_index]) / f64::from(iterations),
+        }
+    }
+}
+
+// Runs out `iterations` random boards against the given hole cards and counts
+// how often each player wins outright, splits or loses.
+pub fn simulate_hand(
+    players: Vec<HoleCards>,
+    iterations: u32,
+    rng: &mut impl rand::Rng,
+) -> SimulationResult {
+    let dealt: Vec<Card> = players
+        .iter()
+        .flat_map(|hole| vec![hole.0.clone(), hole.1.clone()])
+        .collect();
+    let remaining: Vec<Card> = full_deck()
+        .into_iter()
+        .filter(|card| !dealt.contains(card))
+        .collect();
+
+    let mut result = SimulationResult {
+        iterations,
+        wins: vec![0; players.len()],
+        splits: vec![0; players.len()],
+        losses: vec![0; players.len()],
+    };
+
+    for _ in 0..iterations {
+        let board: Vec<Card> = remaining.choose_multiple(rng, 5).cloned().collect();
+        let scores: Vec<u32> = players
+            .iter()
+            .map(|hole| {
+                let mut cards = board.clone();
+                cards.push(hole.0.clone());
+                cards.push(hole.1.clone());
+                hand_score(&cards)
+            })
+            .collect();
+
+        let best = scores.iter().max().cloned().unwrap_or_default();
+        let winners = scores.iter().filter(|&&score| score == best).count();
+        for (i, score) in scores.iter().enumerate() {
+            match (*score == best, winners) {
+                (true, 1) => result.wins[i] += 1,
+                (true, _) => result.splits[i] += 1,
+                (false, _) => result.losses[i] += 1,
+            }
+        }
+    }
+
+    result
+}
+
+// Completes a partial board with random cards from `deck`, which shouldn't
+// hold any of the cards already on the board
+pub fn run_out(board: &[Card], deck: &Deck, rng: &mut impl rand::Rng) -> Vec<Card> {
+    let needed = 5 - core::cmp::min(board.len(), 5);
+    let mut runout = board.to_vec();
+    runout.extend(deck.sample_without_replacement(needed, rng));
+    runout
+}
+
+// Ranges
+
+// How many ways there are to hold `rank`. Without a suit that's the number of
+// pocket pairs, C(4, 2) = 6, and with a suit it's the one specific card.
+pub fn combo_count(rank: Rank, suit: Option<Suit>) -> usize {
+    let cards = full_deck()
+        .into_iter()
+        .filter(|card| card.rank() == rank && suit.unwrap_or(card.suit()) == card.suit())
+        .count();
+    match suit {
+        Some(_) => cards,
+        None => cards * (cards - 1) / 2,
+    }
+}
+
+// The fraction of `opponent_range` that shares at least one card with
+// `hole`, since the opponent can't be holding a card the hero has
+pub fn blocker_effect(hole: &HoleCards, opponent_range: &[HoleCards]) -> f64 {
+    if opponent_range.is_empty() {
+        return 0.0;
+    }
+    let holds = |card: &Card| *card == hole.0 || *card == hole.1;
+    let blocked = opponent_range
+        .iter()
+        .filter(|opponent| holds(&opponent.0) || holds(&opponent.1))
+        .count();
+    blocked as f64 / opponent_range.len() as f64
+}
+
+// Bill Chen's formula: score the high card, double it for a pair, add for
+// suits, take away for the gap and add a bit for small connected cards. Half
+// points round up and the odd negative score counts as 0.
+pub fn preflop_hand_strength(hole: &HoleCards) -> f64 {
+    let (high, low) = {
+        let (a, b) = (hole.0.rank().numeric_value(), hole.1.rank().numeric_value());
+        (core::cmp::max(a, b), core::cmp::min(a, b))
+    };
+    let mut score = match high {
+        14 => 10.0,
+        13 => 8.0,
+        12 => 7.0,
+        11 => 6.0,
+        value => f64::from(value) / 2.0,
+    };
+
+    if high == low {
+        score = f64::max(score * 2.0, 5.0);
+    } else {
+        if hole.is_suited() {
+            score += 2.0;
+        }
+        let gap = high - low - 1;
+        score -= match gap {
+            0 => 0.0,
+            1 => 1.0,
+            2 => 2.0,
+            3 => 4.0,
+            _ => 5.0,
+        };
+        if gap <= 1 && high < Rank::Queen.numeric_value() {
+            score += 1.0;
+        }
+    }
+
+    // f64::ceil needs std, but truncating and then rounding up works the
+    // same way
+    let truncated = score as i64 as f64;
+    let rounded = if truncated < score {
+        truncated + 1.0
+    } else {
+        truncated
+    };
+    f64::max(rounded, 0.0)
+}
+
+// Hands worth opening from each position, following Chen's guidelines of
+// tighter ranges the earlier a player has to act
+pub fn position_adjusted_range(position: PlayerPosition) -> Range {
+    let minimum = match position {
+        PlayerPosition::UnderTheGun => 10.0,
+        PlayerPosition::MiddlePosition => 9.0,
+        PlayerPosition::Cutoff => 8.0,
+        PlayerPosition::Button => 6.0,
+        PlayerPosition::SmallBlind => 7.0,
+        PlayerPosition::BigBlind => 5.0,
+    };
+    Range::all_hands().filter(|hole| preflop_hand_strength(hole) >= minimum)
+}
+
+// A set of hole cards an opponent could be holding
+#[derive(Debug, PartialEq, Clone)]
+pub struct Range(Vec<HoleCards>);
+
+impl Range {
+    // Every starting hand, all C(52, 2) = 1326 of them
+    pub fn all_hands() -> Range {
+        let deck = full_deck();
+        Range(
+            combinations(deck.len(), 2)
+                .into_iter()
+                .map(|pair| HoleCards(deck[pair[0]].clone(), deck[pair[1]].clone()))
+                .collect(),
+        )
+    }
+
+    pub fn pocket_pairs() -> Range {
+        Range::all_hands().filter(HoleCards::is_pair)
+    }
+
+    pub fn suited_connectors() -> Range {
+        Range::all_hands().filter(|hole| hole.is_suited() && hole.is_connected())
+    }
+
+    pub fn filter(&self, pred: impl Fn(&HoleCards) -> bool) -> Range {
+        Range(self.0.iter().filter(|hole| pred(hole)).cloned().collect())
+    }
+
+    // Monte Carlo equity of `hero` against `villain`: each iteration picks a
+    // hand from both ranges that doesn't clash with the board or each other
+    // and runs the rest of the board out. Splits count as half a win.
+    pub fn equity_vs_range(
+        hero: &Range,
+        villain: &Range,
+        board: &[Card],
+        iterations: u32,
+        rng: &mut impl rand::Rng,
+    ) -> f64 {
+        let clashes =
+            |hole: &HoleCards, cards: &[Card]| cards.contains(&hole.0) || cards.contains(&hole.1);
+        let hero_hands = hero.filter(|hole| !clashes(hole, board));
+
+        let (mut played, mut won) = (0, 0.0);
+        for _ in 0..iterations {
+            let hero_hole = match hero_hands.0.choose(rng) {
+                Some(hole) => hole,
+                None => break,
+            };
+            let mut dealt = board.to_vec();
+            dealt.extend(vec![hero_hole.0.clone(), hero_hole.1.clone()]);
+            let villain_hands = villain.filter(|hole| !clashes(hole, &dealt));
+            let villain_hole = match villain_hands.0.choose(rng) {
+                Some(hole) => hole,
+                None => continue,
+            };
+            dealt.extend(vec![villain_hole.0.clone(), villain_hole.1.clone()]);
+
+            let remaining: Deck = full_deck()
+                .into_iter()
+                .filter(|card| !dealt.contains(card))
+                .collect();
+            let runout = run_out(board, &remaining, rng);
+
+            let score = |hole: &HoleCards| {
+                let mut cards = runout.clone();
+                cards.extend(vec![hole.0.clone(), hole.1.clone()]);
+                hand_score(&cards)
+            };
+            played += 1;
+            won += match score(hero_hole).cmp(&score(villain_hole)) {
+                core::cmp::Ordering::Greater => 1.0,
+                core::cmp::Ordering::Equal => 0.5,
+                core::cmp::Ordering::Less => 0.0,
+            };
+        }
+
+        match played {
+            0 => 0.0,
+            played => won / played as f64,
+        }
+    }
+
+    pub fn hands(&self) -> &[HoleCards] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poker::Deck;
+    use rand::SeedableRng;
+
+    #[test]
+    fn combo_counts() {
+        assert_eq!(combo_count(Rank::Ace, None), 6);
+        assert_eq!(combo_count(Rank::Two, None), 6);
+        assert_eq!(combo_count(Rank::Ace, Some(Suit::Heart)), 1);
+        assert_eq!(combo_count(Rank::Ten, Some(Suit::Club)), 1);
+    }
+
+    #[test]
+    fn hole_cards_block_opponent_hands_holding_the_same_cards() {
+        let range = vec![hole("As Ks"), hole("As Ad"), hole("Ah Kh"), hole("Qc Qd")];
+        assert!((blocker_effect(&hole("As 2c"), &range) - 0.5).abs() < 1e-9);
+        assert!((blocker_effect(&hole("Js Tc"), &range) - 0.0).abs() < 1e-9);
+        assert!((blocker_effect(&hole("As Kh"), &range) - 0.75).abs() < 1e-9);
+        assert!((blocker_effect(&hole("As Kh"), &[]) - 0.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn chen_formula_scores() {
+        let score = |cards| preflop_hand_strength(&hole(cards));
+        assert_eq!(score("Ah Ad"), 20.0);
+        assert_eq!(score("Ah Kh"), 12.0);
+        assert_eq!(score("Ah Kd"), 10.0);
+        assert_eq!(score("2c 2d"), 5.0);
+        assert_eq!(score("Th 9h"), 8.0);
+        assert_eq!(score("5s 7s"), 6.0);
+        assert_eq!(score("7c 2d"), 0.0);
+    }
+
+    #[test]
+    fn later_positions_open_wider_ranges() {
+        let utg = position_adjusted_range(PlayerPosition::UnderTheGun);
+        let middle = position_adjusted_range(PlayerPosition::MiddlePosition);
+        let cutoff = position_adjusted_range(PlayerPosition::Cutoff);
+        let btn = position_adjusted_range(PlayerPosition::Button);
+        assert!(utg.len() < middle.len());
+        assert!(middle.len() < cutoff.len());
+        assert!(cutoff.len() < btn.len());
+
+        // Range::all_hands orders each pair the way full_deck does
+        assert!(utg.hands().contains(&hole("Ad Ah")));
+        assert!(!utg.hands().contains(&hole("8h 9h")));
+        assert!(btn.hands().contains(&hole("8h 9h")));
+        assert!(!btn.hands().contains(&hole("2d 7c")));
+    }
+
+    #[test]
+    fn ranges_of_starting_hands() {
+        let all = Range::all_hands();
+        assert_eq!(all.len(), 1326);
+
+        assert_eq!(Range::pocket_pairs().len(), 13 * 6);
+        // A-K through A-2, four suits each
+        assert_eq!(Range::suited_connectors().len(), 13 * 4);
+
+        let aces = all.filter(|hole| hole.0.rank() == Rank::Ace || hole.1.rank() == Rank::Ace);
+        assert_eq!(aces.len(), 1326 - combinations(48, 2).len());
+        assert!(all.filter(|_| false).is_empty());
+    }
+
+    #[test]
+    fn equity_between_ranges() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let all = Range::all_hands();
+        let equity = Range::equity_vs_range(&all, &all, &[], 2000, &mut rng);
+        assert!((equity - 0.5).abs() < 0.05, "equity was {}", equity);
+
+        let aces = Range::pocket_pairs().filter(|hole| hole.0.rank() == Rank::Ace);
+        let equity = Range::equity_vs_range(&aces, &all, &[], 2000, &mut rng);
+        assert!(equity > 0.8, "equity was {}", equity);
+
+        // With three aces on the board there are no pocket aces left to hold
+        let board: Vec<Card> = "Ah Ad Ac".parse::<Deck>().unwrap().0;
+        let equity = Range::equity_vs_range(&aces, &all, &board, 100, &mut rng);
+        assert!((equity - 0.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn simulate_hand_rates_sum_to_one() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let result = simulate_hand(
+            vec![
+                HoleCards(Card(Suit::Heart, Rank::Ace), Card(Suit::Spade, Rank::Ace)),
+                HoleCards(
+                    Card(Suit::Club, Rank::Seven),
+                    Card(Suit::Diamond, Rank::Two),
+                ),
+            ],
+            500,
+            &mut rng,
+        );
+
+        for player in 0..2 {
+            let total =
+                result.win_rate(player) + result.split_rate(player) + result.loss_rate(player);
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+        assert!(result.win_rate(0) > 0.7);
+        assert_eq!(
+            result.wins[0] + result.splits[0],
+            result.losses[1] + result.splits[1]
+        );
+    }
+
+    #[test]
+    fn boards_are_run_out_to_five_cards() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let flop: Vec<Card> = "Ah Kd Qc".parse::<Deck>().unwrap().0;
+        let deck: Deck = full_deck()
+            .into_iter()
+            .filter(|card| !flop.contains(card))
+            .collect();
+
+        assert_eq!(run_out(&[], &deck, &mut rng).len(), 5);
+        let board = run_out(&flop, &deck, &mut rng);
+        assert_eq!(board.len(), 5);
+        assert_eq!(&board[..3], &flop[..]);
+        assert!(board[3..].iter().all(|card| deck.0.contains(card)));
+        assert_ne!(board[3], board[4]);
+    }
+
+    fn hole(s: &str) -> HoleCards {
+        let deck: Deck = s.parse().unwrap();
+        HoleCards(deck[0].clone(), deck[1].clone())
+    }
+}
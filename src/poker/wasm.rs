@@ -0,0 +1,52 @@
+// Entry points for JavaScript. Cards, moves and hands cross over as plain
+// objects in the same shape serde gives them, and errors are thrown as
+// strings.
+
+use super::{
+    evaluate_hand, full_deck, Card, Deck, Hand, Player, PlayerMove, SeededShuffler, Shuffler,
+};
+use wasm_bindgen::prelude::*;
+
+fn to_js<T: serde::Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+fn from_js<T: serde::de::DeserializeOwned>(value: JsValue) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+fn error(e: impl core::fmt::Display) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+// Deals a hand to `players_json`, e.g. [{"name":"Will","chips":100}], from a
+// deck shuffled with `seed` so that the same seed deals the same cards
+#[wasm_bindgen]
+pub fn deal_from_js(players_json: &str, seed: u64) -> Result<JsValue, JsValue> {
+    let players: Vec<Player> = serde_json::from_str(players_json).map_err(error)?;
+    let mut deck = full_deck();
+    SeededShuffler { seed }.shuffle(&mut deck);
+    let hand = Hand::try_new(players, deck)
+        .and_then(Hand::deal)
+        .map_err(error)?;
+    to_js(&hand)
+}
+
+// Plays a move such as {"Bet":10} or "Fold" for the active player
+#[wasm_bindgen]
+pub fn play_from_js(hand: JsValue, mv: JsValue) -> Result<JsValue, JsValue> {
+    let hand: Hand = from_js(hand)?;
+    let mv: PlayerMove = from_js(mv)?;
+    to_js(&hand.play(mv).map_err(error)?)
+}
+
+// Classifies five to seven cards written the way Deck parses them, e.g.
+// "Ah Kh Qh Jh Th"
+#[wasm_bindgen]
+pub fn evaluate_hand_from_js(cards: &str) -> Result<JsValue, JsValue> {
+    let cards: Vec<Card> = cards.parse::<Deck>().map_err(error)?.into_iter().collect();
+    if !(5..=7).contains(&cards.len()) {
+        return Err(error(format!("expected 5 to 7 cards, got {}", cards.len())));
+    }
+    to_js(&evaluate_hand(&cards))
+}
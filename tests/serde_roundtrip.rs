@@ -0,0 +1,46 @@
+#![cfg(feature = "serde")]
+
+use poker::poker::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+fn roundtrip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T) {
+    let json = serde_json::to_string(&value).unwrap();
+    let back: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, value, "{}", json);
+}
+
+#[test]
+fn public_types_survive_a_json_roundtrip() {
+    let card: Card = "Ah".parse().unwrap();
+    let hole = Range::pocket_pairs().hands()[0].clone();
+    let players = vec![Player::new("Will", 100), Player::new("Jean", 50)];
+    let hand = new_hand(players.clone(), full_deck())
+        .deal()
+        .unwrap()
+        .play(PlayerMove::Bet(10))
+        .unwrap()
+        .play(PlayerMove::Call(10))
+        .unwrap()
+        .deal_flop()
+        .unwrap();
+
+    roundtrip(Suit::Spade);
+    roundtrip(Rank::Ten);
+    roundtrip(card.clone());
+    roundtrip(full_deck());
+    roundtrip(players[0].clone());
+    roundtrip(PlayerMove::Raise(20));
+    roundtrip(PlayerMove::Discard(vec![card]));
+    roundtrip(hole.clone());
+    roundtrip(PlayerState::Active(hole));
+    roundtrip(PlayerState::SittingOut);
+    let full_house: Vec<Card> = "Ah Ad Ac 2s 2h"
+        .parse::<Deck>()
+        .unwrap()
+        .into_iter()
+        .collect();
+    roundtrip(evaluate_hand(&full_house));
+    roundtrip(hand);
+}
@@ -0,0 +1,27 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use poker::poker::wasm::*;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn hands_dealt_from_js_can_be_played() {
+    let players = r#"[{"name":"Will","chips":100},{"name":"Jean","chips":100}]"#;
+    let hand = deal_from_js(players, 7).unwrap();
+    assert!(hand.is_object());
+
+    let hand = play_from_js(hand, JsValue::from_str("Check")).unwrap();
+    assert!(hand.is_object());
+    assert!(deal_from_js("[]", 7).is_err());
+}
+
+#[wasm_bindgen_test]
+fn cards_are_evaluated_from_js() {
+    assert!(evaluate_hand_from_js("Ah Kh Qh Jh Th").unwrap().is_object());
+    assert!(evaluate_hand_from_js("Ah Zz").is_err());
+    assert_eq!(
+        evaluate_hand_from_js("").unwrap_err(),
+        JsValue::from_str("expected 5 to 7 cards, got 0")
+    );
+    assert!(evaluate_hand_from_js("Ah Kh").is_err());
+}
@@ -0,0 +1,33 @@
+import init, { deal_from_js, play_from_js, evaluate_hand_from_js } from "./pkg/poker.js";
+
+const players = JSON.stringify([
+  { name: "Will", chips: 100 },
+  { name: "Jean", chips: 100 },
+]);
+
+let hand = null;
+
+function show(value) {
+  document.getElementById("hand").textContent = JSON.stringify(value, null, 2);
+}
+
+function play(move) {
+  try {
+    hand = play_from_js(hand, move);
+    show(hand);
+  } catch (error) {
+    show({ error });
+  }
+}
+
+await init();
+
+document.getElementById("deal").onclick = () => {
+  // BigInt because the seed is a u64
+  hand = deal_from_js(players, BigInt(Date.now()));
+  show(hand);
+};
+document.getElementById("check").onclick = () => play("Check");
+document.getElementById("fold").onclick = () => play("Fold");
+
+console.log(evaluate_hand_from_js("Ah Kh Qh Jh Th"));